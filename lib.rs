@@ -1,21 +1,85 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+pub use self::erc20::{
+    ApprovalReceiver, Erc20, Erc20Ref, FlashBorrower, RoundingMode, TokenInfo, TokenReceiver,
+    ON_APPROVAL_RECEIVED, ON_FLASH_LOAN, ON_TOKEN_RECEIVED, PSP22,
+};
+
 #[ink::contract]
 mod erc20 {
     use ink::storage::Mapping;
     use ink::prelude::string::String;
     use ink::prelude::string::ToString;
+    use ink::prelude::vec::Vec;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::CallFlags;
+    use ink::env::hash::{Blake2x256, HashOutput};
 
     /// Defines the storage of your contract.
     /// Add new fields to the below struct in order
     /// to add new static storage fields to your contract.
     #[ink(storage)]
-    #[derive(Default)]
     pub struct Erc20 {
         /// Stores a single `bool` value on the storage.
         total_supply: Balance,
         balances: Mapping<AccountId, Balance>,
-        allowances: Mapping<(AccountId, AccountId), Balance>,
+        /// Allowances keyed by `allowance_key(owner, spender)`.
+        allowances: Mapping<AllowanceKey, Allowance>,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        owner: AccountId,
+        pending_owner: Option<AccountId>,
+        paused: bool,
+        frozen: Mapping<AccountId, bool>,
+        cap: Option<Balance>,
+        nonces: Mapping<AccountId, u64>,
+        current_snapshot_id: u32,
+        account_snapshots: Mapping<AccountId, BalanceSnapshots>,
+        total_supply_snapshots: Mapping<u32, Balance>,
+        fee_bps: u16,
+        treasury: AccountId,
+        locked: bool,
+        /// Set while `with_callback` waits on another contract.
+        in_callback: bool,
+        roles: Mapping<(RoleId, AccountId), bool>,
+        holder_count: u32,
+        spenders: Mapping<AccountId, Vec<AccountId>>,
+        vesting: Mapping<AccountId, VestingSchedule>,
+        vesting_released: Mapping<AccountId, Balance>,
+        delegates: Mapping<AccountId, AccountId>,
+        vote_checkpoints: Mapping<AccountId, VoteCheckpoints>,
+        total_burned: Balance,
+        excluded: Mapping<AccountId, bool>,
+        excluded_balance_total: Balance,
+        max_transfer_per_window: Balance,
+        window_secs: u64,
+        transfer_windows: Mapping<AccountId, (u64, Balance)>,
+        deposited: Balance,
+        distributions: Mapping<u32, Balance>,
+        claimed: Mapping<(u32, AccountId), bool>,
+        paused_ops: u8,
+        min_balance: Balance,
+        token_id: u32,
+        max_allowance: Option<Balance>,
+        escrowed: Balance,
+        flash_fee_bps: u16,
+        authorizations: Mapping<AuthorizationKey, bool>,
+        transfer_cooldown_secs: u64,
+        last_transfer: Mapping<AccountId, u64>,
+        locked_balances: Mapping<AccountId, Balance>,
+        processed_bridge_txs: Mapping<[u8; 32], bool>,
+        low_balance_threshold: Balance,
+        fee_exempt: Mapping<AccountId, bool>,
+        transfer_seq: u64,
+        allowlist_enabled: bool,
+        allowed: Mapping<AccountId, bool>,
+        total_accounts_ever: u32,
+        seen: Mapping<AccountId, bool>,
+        migrated: Mapping<AccountId, bool>,
+        rounding: RoundingMode,
+        genesis: GenesisInfo,
+        migration_source: Option<AccountId>,
     }
 
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -23,9 +87,229 @@ mod erc20 {
     pub enum Error {
         InsufficientBalance,
         InsufficientAllowance,
+        SupplyOverflow,
+        AllowanceOverflow,
+        BalanceOverflow,
+        NotOwner,
+        Paused,
+        AccountFrozen,
+        CapExceeded,
+        DataTooLarge,
+        PermitExpired,
+        InvalidSignature,
+        InvalidSnapshot,
+        InvalidFee,
+        Reentrant,
+        MissingRole,
+        NotPendingOwner,
+        TransferFailed,
+        VestingExists,
+        NoVesting,
+        CallbackFailed,
+        TransferLimitExceeded,
+        NonReceiver,
+        ZeroAmount,
+        DistributionExists,
+        NoDistribution,
+        AlreadyClaimed,
+        UpgradeFailed,
+        DustBalance,
+        AllowanceTooHigh,
+        AuthorizationUsed,
+        Cooldown,
+        InsufficientUnlockedBalance,
+        AlreadyProcessed,
+        SupplyNotZero,
+        ArithmeticOverflow,
+        CannotFreezeOwner,
+        NotAllowed,
+        BatchTooLarge,
+        MetadataTooLong,
+        EmptyMetadata,
+        AlreadyMigrated,
+        ZeroAddress,
+        MigrationNotAllowed,
+    }
+
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Token metadata and supply, as returned by `token_info`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct TokenInfo {
+        pub name: String,
+        pub symbol: String,
+        pub decimals: u8,
+        pub total_supply: Balance,
+    }
+
+    /// How the transfer fee is rounded when it is not a whole number of
+    /// tokens.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum RoundingMode {
+        /// Dust stays with the sender's transfer, favouring the recipient.
+        #[default]
+        Down,
+        /// Dust goes to the treasury.
+        Up,
+        /// Rounds to the closest token, halves going to the treasury.
+        Nearest,
+    }
+
+    impl RoundingMode {
+        /// Returns what to add to a numerator before flooring the division by
+        /// `denominator` to round in this mode.
+        fn offset(self, denominator: Balance) -> Balance {
+            match self {
+                RoundingMode::Down => 0,
+                RoundingMode::Up => denominator - 1,
+                RoundingMode::Nearest => denominator / 2,
+            }
+        }
+    }
+
+    pub type RoleId = u32;
+
+    /// Holders of this role can grant and revoke every role.
+    pub const DEFAULT_ADMIN_ROLE: RoleId = 0;
+    /// Holders of this role can `mint` and `burn_from`.
+    pub const MINTER_ROLE: RoleId = 1;
+    /// Holders of this role can `bridge_mint`.
+    pub const BRIDGE_ROLE: RoleId = 2;
+    /// Holders of this role can `distribute_from_treasury`.
+    pub const TREASURER_ROLE: RoleId = 3;
+
+    /// `paused_ops` bit gating `transfer` and the messages built on it, as
+    /// well as reward distributions and vesting.
+    pub const PAUSE_TRANSFER: u8 = 1 << 0;
+    /// `paused_ops` bit gating `transfer_from`.
+    pub const PAUSE_TRANSFER_FROM: u8 = 1 << 1;
+    /// `paused_ops` bit gating `mint` and `deposit`.
+    pub const PAUSE_MINT: u8 = 1 << 2;
+    /// `paused_ops` bit gating `burn`, `burn_from` and `withdraw`.
+    pub const PAUSE_BURN: u8 = 1 << 3;
+    /// `paused_ops` bit gating new approvals through `approve`,
+    /// `approve_with_expiry`, `increase_allowance` and `permit`. Revoking with
+    /// `approve(spender, 0)` or decreasing an allowance is never paused, so
+    /// holders can still cut off spenders during an incident.
+    pub const PAUSE_APPROVE: u8 = 1 << 4;
+
+    /// Denominator for fees expressed in basis points.
+    const BPS_DENOMINATOR: u16 = 10_000;
+
+    /// Blake2x256 hash of an encoded `(owner, spender)` pair.
+    type AllowanceKey = <Blake2x256 as HashOutput>::Type;
+
+    /// Allowance amount and the timestamp after which it lapses, if any.
+    type Allowance = (Balance, Option<u64>);
+
+    /// `(authorizer, nonce)` of a `transfer_with_authorization`.
+    type AuthorizationKey = (AccountId, [u8; 32]);
+
+    /// `(snapshot id, balance)` pairs ordered by snapshot id.
+    type BalanceSnapshots = Vec<(u32, Balance)>;
+
+    /// `(amount, start timestamp, duration)` of a linear vesting schedule.
+    type VestingSchedule = (Balance, u64, u64);
+
+    /// `(initial supply, deployer, block timestamp)` recorded at construction.
+    type GenesisInfo = (Balance, AccountId, u64);
+
+    /// `(block timestamp, votes)` pairs ordered by timestamp.
+    type VoteCheckpoints = Vec<(u64, Balance)>;
+
+    /// Maximum number of vote checkpoints kept per account; older ones are dropped.
+    pub const MAX_VOTE_CHECKPOINTS: usize = 32;
+
+    /// Maximum length of the payload accepted by `transfer_with_data`.
+    pub const MAX_TRANSFER_DATA_LEN: usize = 256;
+
+    /// Maximum length of the foreign address accepted by `bridge_burn`.
+    pub const MAX_BRIDGE_RECIPIENT_LEN: usize = 64;
+
+    /// Maximum length in bytes of the token name.
+    pub const MAX_NAME_LEN: usize = 64;
+
+    /// Maximum length in bytes of the token symbol.
+    pub const MAX_SYMBOL_LEN: usize = 12;
+
+    /// Maximum number of entries in a single `transfer_batch`,
+    /// `distribute_from_treasury` or `new_with_allocations` call.
+    pub const MAX_BATCH: usize = 64;
+
+    /// The PSP22 fungible token interface.
+    ///
+    /// Other contracts can call this token generically through this trait,
+    /// e.g. via `contract_ref!(PSP22)`, instead of depending on `Erc20`.
+    #[ink::trait_definition]
+    pub trait PSP22 {
+        #[ink(message)]
+        fn total_supply(&self) -> Balance;
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance;
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
+
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()>;
+
+        #[ink(message)]
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<()>;
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()>;
+
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta: Balance) -> Result<()>;
+
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta: Balance) -> Result<()>;
+    }
+
+    /// Interface for contracts that want to be notified when they receive
+    /// tokens through `transfer_and_call`.
+    #[ink::trait_definition]
+    pub trait TokenReceiver {
+        /// Called after `value` tokens were transferred from `from` to this
+        /// contract. Must return `ON_TOKEN_RECEIVED` to accept them.
+        #[ink(message)]
+        fn on_token_received(&mut self, from: AccountId, value: Balance, data: Vec<u8>) -> [u8; 4];
+    }
+
+    /// Selector of `TokenReceiver::on_token_received`, also used as its accept value.
+    pub const ON_TOKEN_RECEIVED: [u8; 4] = ink::selector_bytes!("TokenReceiver::on_token_received");
+
+    /// Interface for contracts borrowing through `flash_loan`.
+    #[ink::trait_definition]
+    pub trait FlashBorrower {
+        /// Called once `amount` tokens were minted to this contract. The
+        /// borrower must hold `amount + fee` when it returns `ON_FLASH_LOAN`.
+        #[ink(message)]
+        fn on_flash_loan(&mut self, amount: Balance, fee: Balance, data: Vec<u8>) -> [u8; 4];
+    }
+
+    /// Selector of `FlashBorrower::on_flash_loan`, also used as its accept value.
+    pub const ON_FLASH_LOAN: [u8; 4] = ink::selector_bytes!("FlashBorrower::on_flash_loan");
+
+    /// Interface for contracts that want to be notified when they are
+    /// approved through `approve_and_call`.
+    #[ink::trait_definition]
+    pub trait ApprovalReceiver {
+        /// Called after `owner` approved this contract to spend `value` of
+        /// their tokens. May call back into the token, e.g. to `transfer_from`.
+        /// Must return `ON_APPROVAL_RECEIVED` to accept the approval.
+        #[ink(message)]
+        fn on_approval_received(&mut self, owner: AccountId, value: Balance, data: Vec<u8>) -> [u8; 4];
     }
 
-    type Result<T> = core::result::Result<T, Error>;
+    /// Selector of `ApprovalReceiver::on_approval_received`, also used as its accept value.
+    pub const ON_APPROVAL_RECEIVED: [u8; 4] = ink::selector_bytes!("ApprovalReceiver::on_approval_received");
 
     #[ink(event)]
     pub struct Transfer {
@@ -34,6 +318,22 @@ mod erc20 {
         #[ink(topic)]
         to: AccountId,
         value: Balance,
+        /// The caller moving `from`'s tokens through an allowance, if any.
+        #[ink(topic)]
+        spender: Option<AccountId>,
+        /// Position of this event among all `Transfer` events of the contract,
+        /// starting at zero with the first one, usually the constructor's mint.
+        seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct TransferData {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
     }
 
     #[ink(event)]
@@ -45,152 +345,5292 @@ mod erc20 {
         value: Balance,
     }
 
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        from: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Deposit {
+        #[ink(topic)]
+        account: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Withdrawal {
+        #[ink(topic)]
+        account: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct DelegateChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        from_delegate: Option<AccountId>,
+        to_delegate: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct DelegateVotesChanged {
+        #[ink(topic)]
+        delegate: AccountId,
+        previous_votes: Balance,
+        new_votes: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Snapshot {
+        id: u32,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous: AccountId,
+        #[ink(topic)]
+        new: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        account: AccountId,
+    }
+
+    /// Emitted when a transfer drops the sender's balance below
+    /// `low_balance_threshold`.
+    #[ink(event)]
+    pub struct LowBalanceWarning {
+        #[ink(topic)]
+        account: AccountId,
+        balance: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ForcedTransfer {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        value: Balance,
+    }
+
+    /// Emitted ahead of the `Transfer` events of every account-to-account
+    /// transfer, tagged with the token's `token_id`. Mints and burns, which
+    /// lack a sender or a recipient, emit their `Transfer` without it.
+    #[ink(event)]
+    pub struct TransferMeta {
+        #[ink(topic)]
+        token_id: u32,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct BridgeBurn {
+        #[ink(topic)]
+        from: AccountId,
+        value: Balance,
+        #[ink(topic)]
+        dest_chain: u32,
+        dest_recipient: Vec<u8>,
+    }
+
+    #[ink(event)]
+    pub struct BridgeMint {
+        #[ink(topic)]
+        to: AccountId,
+        value: Balance,
+        #[ink(topic)]
+        src_chain: u32,
+        src_tx: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct CodeUpgraded {
+        code_hash: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct MetadataUpdated {
+        name: String,
+        symbol: String,
+        decimals: u8,
+    }
+
     impl Erc20 {
+        /// Instantiates the token with zero supply; tokens enter circulation
+        /// through `mint` or `deposit`.
+        #[ink(constructor)]
+        #[allow(clippy::should_implement_trait)]
+        pub fn default() -> Self {
+            Self::new(0)
+        }
+
         #[ink(constructor)]
         pub fn new(total_supply: Balance) -> Self {
+            Self::init(total_supply, "my-token".to_string(), "BTCF".to_string(), 8)
+        }
+
+        /// Fails if `name` or `symbol` is empty or longer than `MAX_NAME_LEN`
+        /// or `MAX_SYMBOL_LEN` bytes.
+        #[ink(constructor)]
+        pub fn new_with_metadata(total_supply: Balance, name: String, symbol: String, decimals: u8) -> Result<Self> {
+            Self::validate_metadata(&name, &symbol)?;
+            Ok(Self::init(total_supply, name, symbol, decimals))
+        }
+
+        fn init(total_supply: Balance, name: String, symbol: String, decimals: u8) -> Self {
             let mut balances = Mapping::default();
             let caller = Self::env().caller();
-            balances.insert(caller, &total_supply);
-            Self::env().emit_event(Transfer {
-                from: None,
-                to: caller,
-                value: total_supply,
-            });
-            Self {
+            let token_id = Self::metadata_token_id(&name, &symbol);
+            // A zero-supply deployment mints nothing, so it credits no balance
+            // and emits no `Transfer`; `new_with_allocations` relies on this.
+            if total_supply > 0 {
+                balances.insert(caller, &total_supply);
+                Self::emit_event(Transfer {
+                    from: None,
+                    to: caller,
+                    value: total_supply,
+                    spender: None,
+                    seq: 0,
+                });
+            }
+            let mut instance = Self {
                 total_supply,
                 balances,
                 allowances: Default::default(),
+                name,
+                symbol,
+                decimals,
+                owner: caller,
+                pending_owner: None,
+                paused: false,
+                frozen: Default::default(),
+                cap: None,
+                nonces: Default::default(),
+                current_snapshot_id: 0,
+                account_snapshots: Default::default(),
+                total_supply_snapshots: Default::default(),
+                fee_bps: 0,
+                treasury: caller,
+                locked: false,
+                in_callback: false,
+                roles: Default::default(),
+                holder_count: u32::from(total_supply > 0),
+                spenders: Default::default(),
+                vesting: Default::default(),
+                vesting_released: Default::default(),
+                delegates: Default::default(),
+                vote_checkpoints: Default::default(),
+                total_burned: 0,
+                excluded: Default::default(),
+                excluded_balance_total: 0,
+                max_transfer_per_window: 0,
+                window_secs: 0,
+                transfer_windows: Default::default(),
+                deposited: 0,
+                distributions: Default::default(),
+                claimed: Default::default(),
+                paused_ops: 0,
+                min_balance: 0,
+                token_id,
+                max_allowance: None,
+                escrowed: 0,
+                flash_fee_bps: 0,
+                authorizations: Default::default(),
+                transfer_cooldown_secs: 0,
+                last_transfer: Default::default(),
+                locked_balances: Default::default(),
+                processed_bridge_txs: Default::default(),
+                low_balance_threshold: 0,
+                fee_exempt: Default::default(),
+                transfer_seq: u64::from(total_supply > 0),
+                allowlist_enabled: false,
+                allowed: Default::default(),
+                total_accounts_ever: u32::from(total_supply > 0),
+                seen: Default::default(),
+                migrated: Default::default(),
+                rounding: RoundingMode::Down,
+                genesis: (total_supply, caller, Self::env().block_timestamp()),
+                migration_source: None,
+            };
+            if total_supply > 0 {
+                instance.seen.insert(caller, &true);
+            }
+            instance.roles.insert((DEFAULT_ADMIN_ROLE, caller), &true);
+            instance.roles.insert((MINTER_ROLE, caller), &true);
+            instance
+        }
+
+        /// Seeds each listed account with its allocation, emitting one mint
+        /// `Transfer` per entry. Fails if the allocations sum past `Balance::MAX`.
+        #[ink(constructor)]
+        pub fn new_with_allocations(allocations: Vec<(AccountId, Balance)>) -> Result<Self> {
+            Self::ensure_batch_size(allocations.len())?;
+            let mut instance = Self::new(0);
+            for (account, value) in allocations {
+                instance.mint_impl(&account, value)?;
+            }
+            instance.genesis.0 = instance.total_supply;
+            Ok(instance)
+        }
+
+        #[ink(constructor)]
+        pub fn new_capped(initial_supply: Balance, cap: Balance) -> Result<Self> {
+            if initial_supply > cap {
+                return Err(Error::CapExceeded);
+            }
+            let mut instance = Self::new(initial_supply);
+            instance.cap = Some(cap);
+            Ok(instance)
+        }
+
+        /// Like `new_with_metadata`, with `max_supply` as the supply ceiling.
+        /// Fails with `Error::CapExceeded` if `total_supply` is above it.
+        #[ink(constructor)]
+        pub fn new_with_max_supply(
+            total_supply: Balance,
+            name: String,
+            symbol: String,
+            decimals: u8,
+            max_supply: Balance,
+        ) -> Result<Self> {
+            if total_supply > max_supply {
+                return Err(Error::CapExceeded);
             }
+            let mut instance = Self::new_with_metadata(total_supply, name, symbol, decimals)?;
+            instance.cap = Some(max_supply);
+            Ok(instance)
         }
 
         #[ink(message)]
         pub fn name(&self) -> String {
-            "my-token".to_string()
+            self.name.clone()
         }
 
         #[ink(message)]
         pub fn symbol(&self) -> String {
-            "BTCF".to_string()
+            self.symbol.clone()
         }
 
         #[ink(message)]
         pub fn decimals(&self) -> u8 {
-            8
+            self.decimals
         }
 
+        /// Returns the total supply at construction, the deployer and the
+        /// block timestamp of the deployment.
         #[ink(message)]
-        pub fn total_supply(&self) -> Balance {
-            self.total_supply
+        pub fn genesis_info(&self) -> GenesisInfo {
+            self.genesis
         }
 
+        /// Returns the identifier tagging this token's `TransferMeta` events.
         #[ink(message)]
-        pub fn balance_of(&self, owner: AccountId) -> Balance {
-            self.balance_of_impl(&owner)
+        pub fn token_id(&self) -> u32 {
+            self.token_id
         }
 
-        #[inline]
-        fn balance_of_impl(&self, owner: &AccountId) -> Balance {
-            self.balances.get(owner).unwrap_or_default()
+        /// Derives a token id from the metadata the token is deployed with. It
+        /// is fixed at instantiation and does not follow `set_metadata`.
+        fn metadata_token_id(name: &str, symbol: &str) -> u32 {
+            let mut hash = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_encoded::<Blake2x256, _>(&(name, symbol), &mut hash);
+            u32::from_le_bytes([hash[0], hash[1], hash[2], hash[3]])
         }
 
+        /// Returns name, symbol, decimals and total supply in a single call.
         #[ink(message)]
-        pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
-            let caller = self.env().caller();
-            self.transfer_from_to(&caller, &to, value)
+        pub fn token_info(&self) -> TokenInfo {
+            TokenInfo {
+                name: self.name.clone(),
+                symbol: self.symbol.clone(),
+                decimals: self.decimals,
+                total_supply: self.total_supply,
+            }
         }
 
+        /// Renames the token. Only callable by the owner.
+        ///
+        /// `decimals` stays fixed: changing it would silently rescale every
+        /// stored balance. Use `set_decimals` before anything is minted.
         #[ink(message)]
-        pub fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<()> {
-            let caller = self.env().caller();
-            let allowance = self.allowance_impl(&from, &caller);
-            if allowance < value {
-                return Err(Error::InsufficientAllowance);
+        pub fn set_metadata(&mut self, name: String, symbol: String) -> Result<()> {
+            self.ensure_owner()?;
+            Self::validate_metadata(&name, &symbol)?;
+            self.name = name;
+            self.symbol = symbol;
+            Self::emit_event(MetadataUpdated {
+                name: self.name.clone(),
+                symbol: self.symbol.clone(),
+                decimals: self.decimals,
+            });
+            Ok(())
+        }
+
+        /// Bounds the name and symbol to `MAX_NAME_LEN` and `MAX_SYMBOL_LEN`
+        /// bytes and rejects empty ones.
+        fn validate_metadata(name: &str, symbol: &str) -> Result<()> {
+            if name.is_empty() || symbol.is_empty() {
+                return Err(Error::EmptyMetadata);
+            }
+            if name.len() > MAX_NAME_LEN || symbol.len() > MAX_SYMBOL_LEN {
+                return Err(Error::MetadataTooLong);
             }
-            self.allowances.insert((from, caller), &(allowance - value));
-            self.transfer_from_to(&from, &to, value)
+            Ok(())
         }
 
+        /// Changes `decimals`. Only callable by the owner, and only while the
+        /// total supply is zero.
         #[ink(message)]
-        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
-            let owner = self.env().caller();
-            let owner_balance = self.balance_of_impl(&owner);
-            if owner_balance < value {
-                return Err(Error::InsufficientBalance);
+        pub fn set_decimals(&mut self, decimals: u8) -> Result<()> {
+            self.ensure_owner()?;
+            if self.total_supply != 0 {
+                return Err(Error::SupplyNotZero);
             }
-            self.allowances.insert((owner, spender), &value);
-            Self::env().emit_event(Approval {
-                from: owner,
-                to: spender,
-                value,
+            self.decimals = decimals;
+            Self::emit_event(MetadataUpdated {
+                name: self.name.clone(),
+                symbol: self.symbol.clone(),
+                decimals,
             });
             Ok(())
         }
 
+        /// Splits a raw amount into its integer and fractional parts according
+        /// to `decimals`, e.g. `123_45000000` with 8 decimals is `(123, 45000000)`.
         #[ink(message)]
-        pub fn allowance(&mut self, owner: AccountId, spender: AccountId) -> Balance {
-            self.allowance_impl(&owner, &spender)
+        pub fn to_display_units(&self, raw: Balance) -> (Balance, Balance) {
+            match (10 as Balance).checked_pow(u32::from(self.decimals())) {
+                Some(unit) => (raw / unit, raw % unit),
+                // One whole token is larger than any representable amount.
+                None => (0, raw),
+            }
         }
 
-        #[inline]
-        fn allowance_impl(&self, owner: &AccountId, spender: &AccountId) -> Balance {
-            self.allowances.get((owner, spender)).unwrap_or_default()
+        /// Returns the number of accounts currently holding a non-zero balance.
+        #[ink(message)]
+        pub fn holder_count(&self) -> u32 {
+            self.holder_count
         }
 
-        fn transfer_from_to(&mut self, from: &AccountId, to:  &AccountId, value: Balance)-> Result<()> {
-            let from_balance = self.balance_of_impl(from);
-            if from_balance < value {
-                return Err(Error::InsufficientBalance);
-            }
-            let to_balance = self.balance_of_impl(to);
-            self.balances.insert(from, &(from_balance - value));
-            self.balances.insert(to, &(to_balance + value));
-            Self::env().emit_event(Transfer {
-                from: Some(*from),
-                to: *to,
-                value,
-            });
-            Ok(())
+        /// Returns the number of distinct accounts that ever held a non-zero
+        /// balance. Unlike `holder_count` it never goes down.
+        #[ink(message)]
+        pub fn total_accounts_ever(&self) -> u32 {
+            self.total_accounts_ever
         }
-    }
 
+        /// Returns whether `who` has a balance entry at all, which is the case
+        /// for accounts drained to zero but not for accounts never credited.
+        #[ink(message)]
+        pub fn account_has_balance(&self, who: AccountId) -> bool {
+            self.balances.contains(who)
+        }
 
-    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
-    /// module and test functions are marked with a `#[test]` attribute.
-    /// The below code is technically just normal Rust code.
-    #[cfg(test)]
-    mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
-
-        #[ink::test]
-        fn test_all() {
-            let total_supply = 1000000000;
-            let mut erc20 = Erc20::new(1000000000);
-            assert_eq!(total_supply, erc20.total_supply());
+        /// Returns the balances of `owners` in a single call, in input order.
+        #[ink(message)]
+        pub fn balances_of(&self, owners: Vec<AccountId>) -> Vec<Balance> {
+            owners.iter().map(|owner| self.balance_of_impl(owner)).collect()
+        }
 
-            let accounts =
-                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
-            // Alice owns all the tokens on contract instantiation
-            assert_eq!(erc20.balance_of(accounts.alice), total_supply);
-            // Bob does not owns tokens
-            assert_eq!(erc20.balance_of(accounts.bob), 0);
+        #[inline]
+        fn balance_of_impl(&self, owner: &AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or_default()
+        }
 
-            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
-            assert_eq!(erc20.balance_of(accounts.alice), total_supply - 100);
-            assert_eq!(erc20.balance_of(accounts.bob), 100);
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
 
-            assert_eq!(erc20.approve(accounts.charlie, 5000), Ok(()));
-            assert_eq!(erc20.allowance(accounts.alice, accounts.charlie), 5000);
+        #[ink(message)]
+        pub fn pending_owner(&self) -> Option<AccountId> {
+            self.pending_owner
+        }
 
+        /// Nominates `new_owner`, who must call `accept_ownership` to take over.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_not_frozen(&new_owner)?;
+            self.pending_owner = Some(new_owner);
+            Ok(())
+        }
 
-            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
-            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract);
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            if self.pending_owner != Some(caller) {
+                return Err(Error::NotPendingOwner);
+            }
+            self.ensure_not_frozen(&caller)?;
+            self.pending_owner = None;
+            self.set_owner(caller);
+            Ok(())
+        }
+
+        /// Gives up ownership by handing it to the zero account, permanently
+        /// disabling all owner-only messages along with the `DEFAULT_ADMIN_ROLE`
+        /// and `MINTER_ROLE` powers, which then fail with `Error::NotOwner`.
+        /// Any pending nomination is dropped, so neither ownership nor those
+        /// powers can be regained. Accounts already holding `BRIDGE_ROLE` or
+        /// `TREASURER_ROLE` keep using it, but no role can be granted or
+        /// revoked anymore.
+        #[ink(message)]
+        pub fn renounce_ownership(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.pending_owner = None;
+            self.set_owner(AccountId::from([0u8; 32]));
+            Ok(())
+        }
+
+        /// Returns whether ownership has been renounced.
+        #[ink(message)]
+        pub fn is_renounced(&self) -> bool {
+            self.owner == AccountId::from([0u8; 32])
+        }
+
+        /// Moves `from`'s tokens without an allowance, for compliance seizures
+        /// or recovering from compromised keys. Only callable by the owner.
+        ///
+        /// Freezes, the allowlist, locks, the minimum balance, cooldowns,
+        /// transfer quotas and the transfer fee do not apply, so frozen
+        /// accounts can be seized from.
+        #[ink(message)]
+        pub fn force_transfer(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.before_token_transfer(Some(&from), Some(&to), value)?;
+            self.move_tokens(&from, &to, value, None, 0)?;
+            Self::emit_event(ForcedTransfer { from, to, value });
+            Ok(())
+        }
+
+        /// Replaces the contract code with the uploaded `code_hash`, keeping
+        /// storage. The new code must preserve the storage layout.
+        #[ink(message)]
+        pub fn set_code_hash(&mut self, code_hash: [u8; 32]) -> Result<()> {
+            self.ensure_owner()?;
+            ink::env::set_code_hash(&code_hash).map_err(|_| Error::UpgradeFailed)?;
+            Self::emit_event(CodeUpgraded { code_hash });
+            Ok(())
+        }
+
+        /// Runs `f` with the reentrancy lock held, failing with
+        /// `Error::Reentrant` if the lock is already taken or a callback is in
+        /// flight.
+        fn non_reentrant<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+            self.ensure_not_in_callback()?;
+            self.non_reentrant_transfer(f)
+        }
+
+        /// Like `non_reentrant`, but also runs while a callback is in flight,
+        /// so borrowers and spenders can move tokens from it.
+        fn non_reentrant_transfer<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+            if self.locked {
+                return Err(Error::Reentrant);
+            }
+            self.locked = true;
+            let result = f(self);
+            self.locked = false;
+            result
+        }
+
+        fn ensure_not_in_callback(&self) -> Result<()> {
+            if self.in_callback {
+                return Err(Error::Reentrant);
+            }
+            Ok(())
+        }
+
+        /// Runs `call`, a cross-contract call that may reenter this contract.
+        ///
+        /// The root storage is written out before the call, so reentrant
+        /// messages see this message's changes, and reloaded afterwards, so
+        /// their changes are not overwritten with a stale copy. While the call
+        /// runs only token movements and approvals go through: owner and role
+        /// gated messages, the other `non_reentrant` messages and nested
+        /// callbacks fail with `Error::Reentrant`.
+        fn with_callback<T>(&mut self, call: impl FnOnce(&Self) -> T) -> Result<T> {
+            self.ensure_not_in_callback()?;
+            let key = <Self as ink::storage::traits::StorageKey>::KEY;
+            self.in_callback = true;
+            ink::env::set_contract_storage(&key, self);
+            let result = call(self);
+            if let Ok(Some(root)) = ink::env::get_contract_storage::<_, Self>(&key) {
+                *self = root;
+            }
+            self.in_callback = false;
+            Ok(result)
+        }
+
+        fn ensure_owner(&self) -> Result<()> {
+            self.ensure_not_in_callback()?;
+            if self.is_renounced() || self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            Ok(())
+        }
+
+        fn set_owner(&mut self, new_owner: AccountId) {
+            let previous = self.owner;
+            self.owner = new_owner;
+            Self::emit_event(OwnershipTransferred {
+                previous,
+                new: new_owner,
+            });
+        }
+
+        #[ink(message)]
+        pub fn has_role(&self, role: RoleId, account: AccountId) -> bool {
+            self.roles.get((role, account)).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: RoleId, account: AccountId) -> Result<()> {
+            self.ensure_role(DEFAULT_ADMIN_ROLE)?;
+            self.roles.insert((role, account), &true);
+            Self::emit_event(RoleGranted {
+                role,
+                account,
+                sender: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: RoleId, account: AccountId) -> Result<()> {
+            self.ensure_role(DEFAULT_ADMIN_ROLE)?;
+            self.roles.remove((role, account));
+            Self::emit_event(RoleRevoked {
+                role,
+                account,
+                sender: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        fn ensure_role(&self, role: RoleId) -> Result<()> {
+            self.ensure_not_in_callback()?;
+            // Renouncing ownership retires role administration and minting
+            // along with it.
+            if self.is_renounced() && matches!(role, DEFAULT_ADMIN_ROLE | MINTER_ROLE) {
+                return Err(Error::NotOwner);
+            }
+            if !self.has_role(role, self.env().caller()) {
+                return Err(Error::MissingRole);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn paused(&self) -> bool {
+            self.paused
+        }
+
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.paused = true;
+            Self::emit_event(Paused {
+                account: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.paused = false;
+            Self::emit_event(Unpaused {
+                account: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        /// Returns the `PAUSE_*` bits of the operations paused individually.
+        #[ink(message)]
+        pub fn paused_ops(&self) -> u8 {
+            self.paused_ops
+        }
+
+        /// Pauses exactly the operations whose `PAUSE_*` bits are set in
+        /// `flags`, on top of the global `pause`.
+        #[ink(message)]
+        pub fn set_paused_ops(&mut self, flags: u8) -> Result<()> {
+            self.ensure_owner()?;
+            self.paused_ops = flags;
+            Ok(())
+        }
+
+        fn ensure_not_paused(&self, op: u8) -> Result<()> {
+            if self.paused || self.paused_ops & op != 0 {
+                return Err(Error::Paused);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_frozen(&self, account: AccountId) -> bool {
+            self.frozen.get(account).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn freeze(&mut self, account: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            // A frozen owner could no longer be replaced.
+            if account == self.owner {
+                return Err(Error::CannotFreezeOwner);
+            }
+            self.frozen.insert(account, &true);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unfreeze(&mut self, account: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.frozen.remove(account);
+            Ok(())
+        }
+
+        fn ensure_not_frozen(&self, account: &AccountId) -> Result<()> {
+            if self.frozen.get(account).unwrap_or_default() {
+                return Err(Error::AccountFrozen);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn allowlist_enabled(&self) -> bool {
+            self.allowlist_enabled
+        }
+
+        /// Restricts transfers to allowlisted senders and recipients while
+        /// enabled. Only callable by the owner.
+        #[ink(message)]
+        pub fn set_allowlist_enabled(&mut self, enabled: bool) -> Result<()> {
+            self.ensure_owner()?;
+            self.allowlist_enabled = enabled;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_allowed(&self, account: AccountId) -> bool {
+            self.allowed.contains(account)
+        }
+
+        #[ink(message)]
+        pub fn set_allowed(&mut self, account: AccountId, allowed: bool) -> Result<()> {
+            self.ensure_owner()?;
+            if allowed {
+                self.allowed.insert(account, &true);
+            } else {
+                self.allowed.remove(account);
+            }
+            Ok(())
+        }
+
+        /// The contract's own account is always allowed, so escrowed tokens
+        /// can still be paid out to allowlisted accounts.
+        fn ensure_allowed(&self, account: &AccountId) -> Result<()> {
+            if self.allowlist_enabled && *account != self.env().account_id() && !self.is_allowed(*account) {
+                return Err(Error::NotAllowed);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_excluded(&self, account: AccountId) -> bool {
+            self.excluded.get(account).unwrap_or_default()
+        }
+
+        /// Marks `account` (treasury, vesting, burn sink, ...) as held outside
+        /// the circulating supply, or includes it again.
+        #[ink(message)]
+        pub fn set_excluded(&mut self, account: AccountId, excluded: bool) -> Result<()> {
+            self.ensure_owner()?;
+            if self.is_excluded(account) == excluded {
+                return Ok(());
+            }
+            let balance = self.balance_of_impl(&account);
+            if excluded {
+                self.excluded_balance_total = Self::add(self.excluded_balance_total, balance)?;
+                self.excluded.insert(account, &true);
+            } else {
+                self.excluded_balance_total = Self::sub(self.excluded_balance_total, balance)?;
+                self.excluded.remove(account);
+            }
+            Ok(())
+        }
+
+        /// Returns whether the maintained aggregates are consistent with the
+        /// total supply, as far as that can be verified without iterating
+        /// over accounts. Meant for tests and monitoring.
+        #[ink(message)]
+        pub fn check_invariant(&self) -> bool {
+            let contract = self.env().account_id();
+            (self.holder_count == 0) == (self.total_supply == 0)
+                && self.excluded_balance_total <= self.total_supply
+                && self.escrowed <= self.balance_of_impl(&contract)
+                && self.total_supply <= self.cap()
+        }
+
+        /// Returns the total supply minus the balances of excluded accounts.
+        #[ink(message)]
+        pub fn circulating_supply(&self) -> Balance {
+            self.total_supply.saturating_sub(self.excluded_balance_total)
+        }
+
+        /// Returns the part of `who`'s balance that cannot be transferred or burned.
+        #[ink(message)]
+        pub fn locked_balance(&self, who: AccountId) -> Balance {
+            self.locked_balances.get(who).unwrap_or_default()
+        }
+
+        /// Locks a further `amount` of `who`'s balance in place, e.g. as
+        /// collateral. Only callable by the owner.
+        #[ink(message)]
+        pub fn lock(&mut self, who: AccountId, amount: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            let locked = self
+                .locked_balance(who)
+                .checked_add(amount)
+                .filter(|locked| *locked <= self.balance_of_impl(&who))
+                .ok_or(Error::InsufficientBalance)?;
+            self.locked_balances.insert(who, &locked);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unlock(&mut self, who: AccountId, amount: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            let locked = self
+                .locked_balance(who)
+                .checked_sub(amount)
+                .ok_or(Error::InsufficientBalance)?;
+            if locked == 0 {
+                self.locked_balances.remove(who);
+            } else {
+                self.locked_balances.insert(who, &locked);
+            }
+            Ok(())
+        }
+
+        /// Returns whether the caller's unlocked balance covers a transfer of
+        /// `value`. Transfer fees are taken out of `value` rather than on top
+        /// of it, so they need no extra balance.
+        #[ink(message)]
+        pub fn can_transfer(&self, value: Balance) -> bool {
+            let caller = self.env().caller();
+            self.balance_of_impl(&caller)
+                .checked_sub(value)
+                .is_some_and(|remaining| remaining >= self.locked_balance(caller))
+        }
+
+        fn ensure_unlocked(&self, who: &AccountId, remaining: Balance) -> Result<()> {
+            if remaining < self.locked_balance(*who) {
+                return Err(Error::InsufficientUnlockedBalance);
+            }
+            Ok(())
+        }
+
+        /// Returns the maximum supply `mint` may reach, `Balance::MAX` if uncapped.
+        #[ink(message)]
+        pub fn cap(&self) -> Balance {
+            self.cap.unwrap_or(Balance::MAX)
+        }
+
+        /// Returns the supply ceiling fixed by `new_capped` or
+        /// `new_with_max_supply`, if any. No message
+        /// can change it after construction.
+        #[ink(message)]
+        pub fn max_supply(&self) -> Option<Balance> {
+            self.cap
+        }
+
+        #[ink(message)]
+        pub fn max_batch(&self) -> u32 {
+            MAX_BATCH as u32
+        }
+
+        fn ensure_batch_size(len: usize) -> Result<()> {
+            if len > MAX_BATCH {
+                return Err(Error::BatchTooLarge);
+            }
+            Ok(())
+        }
+
+        /// Transfers to many recipients at once, failing before any balance is
+        /// touched if the caller cannot cover the sum.
+        #[ink(message)]
+        pub fn transfer_batch(&mut self, recipients: Vec<(AccountId, Balance)>) -> Result<()> {
+            Self::ensure_batch_size(recipients.len())?;
+            self.ensure_not_paused(PAUSE_TRANSFER)?;
+            let caller = self.env().caller();
+            let total = recipients
+                .iter()
+                .try_fold(0 as Balance, |total, (_, value)| total.checked_add(*value))
+                .ok_or(Error::InsufficientBalance)?;
+            if self.balance_of_impl(&caller) < total {
+                return Err(Error::InsufficientBalance);
+            }
+            for (to, value) in recipients {
+                self.transfer_from_to(&caller, &to, value, None)?;
+            }
+            Ok(())
+        }
+
+        /// Transfers as much of `max_value` as the caller's unlocked balance
+        /// and remaining `transfer_quota` cover and returns the amount actually
+        /// moved. Nothing happens, and no event is emitted, if that amount is
+        /// zero.
+        ///
+        /// Other restrictions are not clamped against: the transfer still
+        /// fails during a cooldown, if it would leave a dust balance, or if
+        /// either account is frozen or not allowed.
+        #[ink(message)]
+        pub fn transfer_up_to(&mut self, to: AccountId, max_value: Balance) -> Result<Balance> {
+            let caller = self.env().caller();
+            let mut available = self
+                .balance_of_impl(&caller)
+                .saturating_sub(self.locked_balance(caller));
+            // The contract's own account has no quota.
+            if caller != self.env().account_id() {
+                available = available.min(self.transfer_quota(caller).0);
+            }
+            let value = max_value.min(available);
+            if value == 0 {
+                return Ok(0);
+            }
+            self.transfer(to, value)?;
+            Ok(value)
+        }
+
+        /// Like `transfer`, but returns `Ok(false)` instead of an error when the
+        /// caller's balance is too low, as the classic ERC20 interface does.
+        #[ink(message)]
+        pub fn transfer_bool(&mut self, to: AccountId, value: Balance) -> Result<bool> {
+            let caller = self.env().caller();
+            // Checked up front: a soft failure does not revert, so it must not
+            // follow any write.
+            if self.balance_of_impl(&caller) < value {
+                return Ok(false);
+            }
+            self.transfer(to, value).map(|()| true)
+        }
+
+        /// Like `transfer_from`, but returns `Ok(false)` instead of an error
+        /// when the allowance or `from`'s balance is too low.
+        #[ink(message)]
+        pub fn transfer_from_bool(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<bool> {
+            let caller = self.env().caller();
+            if self.allowance_impl(&from, &caller) < value || self.balance_of_impl(&from) < value {
+                return Ok(false);
+            }
+            self.transfer_from(from, to, value).map(|()| true)
+        }
+
+        /// Like `approve`, but returns `Ok(true)` on success.
+        #[ink(message)]
+        pub fn approve_bool(&mut self, spender: AccountId, value: Balance) -> Result<bool> {
+            self.approve(spender, value).map(|()| true)
+        }
+
+        /// Pays out of the contract's own balance to many recipients at once,
+        /// failing before any balance is touched if the unescrowed treasury
+        /// cannot cover the sum.
+        #[ink(message)]
+        pub fn distribute_from_treasury(&mut self, recipients: Vec<(AccountId, Balance)>) -> Result<()> {
+            Self::ensure_batch_size(recipients.len())?;
+            self.ensure_role(TREASURER_ROLE)?;
+            self.ensure_not_paused(PAUSE_TRANSFER)?;
+            let contract = self.env().account_id();
+            let total = recipients
+                .iter()
+                .try_fold(0 as Balance, |total, (_, value)| total.checked_add(*value))
+                .ok_or(Error::InsufficientBalance)?;
+            if self.balance_of_impl(&contract).saturating_sub(self.escrowed) < total {
+                return Err(Error::InsufficientBalance);
+            }
+            for (to, value) in recipients {
+                self.transfer_from_to(&contract, &to, value, None)?;
+            }
+            Ok(())
+        }
+
+        /// Transfers like `transfer` and additionally emits a `TransferData`
+        /// event carrying `data`, e.g. an invoice id or memo.
+        #[ink(message)]
+        pub fn transfer_with_data(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<()> {
+            if data.len() > MAX_TRANSFER_DATA_LEN {
+                return Err(Error::DataTooLarge);
+            }
+            self.transfer(to, value)?;
+            Self::emit_event(TransferData {
+                from: self.env().caller(),
+                to,
+                value,
+                data,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn nonces(&self, owner: AccountId) -> u64 {
+            self.nonces.get(owner).unwrap_or_default()
+        }
+
+        /// Returns `who`'s current nonce. This is the same counter `permit`
+        /// consumes, so off-chain systems can sequence their own signed
+        /// operations against it.
+        #[ink(message)]
+        pub fn nonce_of(&self, who: AccountId) -> u64 {
+            self.nonces(who)
+        }
+
+        /// Increments the caller's nonce, invalidating any outstanding
+        /// signature over the current one.
+        #[ink(message)]
+        pub fn bump_nonce(&mut self) -> Result<()> {
+            let caller = self.env().caller();
+            self.use_nonce(caller)?;
+            Ok(())
+        }
+
+        /// Returns `owner`'s current nonce and advances it.
+        fn use_nonce(&mut self, owner: AccountId) -> Result<u64> {
+            let nonce = self.nonces(owner);
+            let next = nonce.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+            self.nonces.insert(owner, &next);
+            Ok(nonce)
+        }
+
+        /// Sets `spender`'s allowance over `owner`'s tokens from an off-chain
+        /// ECDSA signature by `owner`, so a relayer can pay for the approval.
+        ///
+        /// The signed payload is the hash returned by `permit_hash` for the
+        /// owner's current nonce, which is incremented on success.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: u64,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::PermitExpired);
+            }
+            self.ensure_not_frozen(&owner)?;
+            self.ensure_not_frozen(&spender)?;
+            let nonce = self.nonces(owner);
+            let message_hash = self.permit_hash(owner, spender, value, nonce, deadline);
+            self.ensure_signed_by(owner, &signature, &message_hash)?;
+            if value > 0 {
+                self.ensure_not_paused(PAUSE_APPROVE)?;
+            }
+            self.ensure_allowance_within_max(value)?;
+            self.use_nonce(owner)?;
+            self.write_allowance(&owner, &spender, value, None);
+            Ok(())
+        }
+
+        fn permit_hash(
+            &self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            nonce: u64,
+            deadline: u64,
+        ) -> [u8; 32] {
+            self.env().hash_encoded::<Blake2x256, _>(&(
+                self.env().account_id(),
+                owner,
+                spender,
+                value,
+                nonce,
+                deadline,
+            ))
+        }
+
+        /// Fails with `Error::InvalidSignature` unless `signature` over
+        /// `message_hash` recovers to `signer`.
+        fn ensure_signed_by(
+            &self,
+            signer: AccountId,
+            signature: &[u8; 65],
+            message_hash: &[u8; 32],
+        ) -> Result<()> {
+            let public_key = self
+                .env()
+                .ecdsa_recover(signature, message_hash)
+                .map_err(|_| Error::InvalidSignature)?;
+            let mut account = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&public_key, &mut account);
+            if AccountId::from(account) != signer {
+                return Err(Error::InvalidSignature);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn authorization_state(&self, authorizer: AccountId, nonce: [u8; 32]) -> bool {
+            self.authorizations.contains((authorizer, nonce))
+        }
+
+        /// Executes a transfer signed off-chain by `from` (EIP-3009), so a
+        /// relayer can pay for it.
+        ///
+        /// The signed payload is the hash returned by `authorization_hash`. It
+        /// is only valid strictly between `valid_after` and `valid_before`, and
+        /// each random `nonce` can be used once per `from`.
+        #[allow(clippy::too_many_arguments)]
+        #[ink(message)]
+        pub fn transfer_with_authorization(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            valid_after: u64,
+            valid_before: u64,
+            nonce: [u8; 32],
+            signature: [u8; 65],
+        ) -> Result<()> {
+            let now = self.env().block_timestamp();
+            if now <= valid_after || now >= valid_before {
+                return Err(Error::PermitExpired);
+            }
+            if self.authorization_state(from, nonce) {
+                return Err(Error::AuthorizationUsed);
+            }
+            let message_hash =
+                self.authorization_hash(from, to, value, valid_after, valid_before, nonce);
+            self.ensure_signed_by(from, &signature, &message_hash)?;
+            self.non_reentrant(|erc20| {
+                erc20.ensure_not_paused(PAUSE_TRANSFER)?;
+                erc20.authorizations.insert((from, nonce), &true);
+                erc20.transfer_from_to(&from, &to, value, None)
+            })
+        }
+
+        fn authorization_hash(
+            &self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            valid_after: u64,
+            valid_before: u64,
+            nonce: [u8; 32],
+        ) -> [u8; 32] {
+            self.env().hash_encoded::<Blake2x256, _>(&(
+                self.env().account_id(),
+                from,
+                to,
+                value,
+                valid_after,
+                valid_before,
+                nonce,
+            ))
+        }
+
+        /// Records the current balances under a new snapshot id and returns it.
+        #[ink(message)]
+        pub fn snapshot(&mut self) -> Result<u32> {
+            self.ensure_owner()?;
+            let id = self
+                .current_snapshot_id
+                .checked_add(1)
+                .ok_or(Error::ArithmeticOverflow)?;
+            self.current_snapshot_id = id;
+            self.total_supply_snapshots.insert(id, &self.total_supply);
+            Self::emit_event(Snapshot { id });
+            Ok(id)
+        }
+
+        #[ink(message)]
+        pub fn balance_of_at(&self, account: AccountId, snapshot_id: u32) -> Result<Balance> {
+            if snapshot_id == 0 || snapshot_id > self.current_snapshot_id {
+                return Err(Error::InvalidSnapshot);
+            }
+            let snapshots = self.account_snapshots.get(account).unwrap_or_default();
+            // Entries are only written when a balance first changes after a
+            // snapshot, so the first entry at or after `snapshot_id` holds the
+            // balance as of that snapshot.
+            let index = snapshots.partition_point(|(id, _)| *id < snapshot_id);
+            match snapshots.get(index) {
+                Some((_, balance)) => Ok(*balance),
+                None => Ok(self.balance_of_impl(&account)),
+            }
+        }
+
+        #[ink(message)]
+        pub fn total_supply_at(&self, snapshot_id: u32) -> Result<Balance> {
+            self.total_supply_snapshots
+                .get(snapshot_id)
+                .ok_or(Error::InvalidSnapshot)
+        }
+
+        /// Escrows `total_reward` of the caller's tokens for holders at
+        /// `snapshot_id` to `claim` pro rata.
+        #[ink(message)]
+        pub fn distribute(&mut self, snapshot_id: u32, total_reward: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_not_paused(PAUSE_TRANSFER)?;
+            self.total_supply_at(snapshot_id)?;
+            if self.distributions.contains(snapshot_id) {
+                return Err(Error::DistributionExists);
+            }
+            let caller = self.env().caller();
+            let contract = self.env().account_id();
+            self.transfer_from_to(&caller, &contract, total_reward, None)?;
+            self.distributions.insert(snapshot_id, &total_reward);
+            self.escrowed = Self::add(self.escrowed, total_reward)?;
+            Ok(())
+        }
+
+        /// Returns `account`'s share of the reward distributed at `snapshot_id`,
+        /// rounded down so that all shares together never exceed the reward.
+        #[ink(message)]
+        pub fn claimable(&self, snapshot_id: u32, account: AccountId) -> Result<Balance> {
+            let reward = self
+                .distributions
+                .get(snapshot_id)
+                .ok_or(Error::NoDistribution)?;
+            if self.claimed.contains((snapshot_id, account)) {
+                return Ok(0);
+            }
+            let supply = self.total_supply_at(snapshot_id)?;
+            let balance = self.balance_of_at(account, snapshot_id)?;
+            if supply == 0 {
+                return Ok(0);
+            }
+            // Split the multiplication so that `reward * balance` only overflows
+            // for supplies beyond `sqrt(Balance::MAX)`.
+            let remainder = (reward % supply)
+                .checked_mul(balance)
+                .ok_or(Error::BalanceOverflow)?;
+            Ok(reward / supply * balance + remainder / supply)
+        }
+
+        /// Pays the caller their share of the reward distributed at `snapshot_id`.
+        #[ink(message)]
+        pub fn claim(&mut self, snapshot_id: u32) -> Result<()> {
+            self.ensure_not_paused(PAUSE_TRANSFER)?;
+            let caller = self.env().caller();
+            if self.claimed.contains((snapshot_id, caller)) {
+                return Err(Error::AlreadyClaimed);
+            }
+            let share = self.claimable(snapshot_id, caller)?;
+            let contract = self.env().account_id();
+            self.transfer_from_to(&contract, &caller, share, None)?;
+            self.claimed.insert((snapshot_id, caller), &true);
+            self.escrowed = Self::sub(self.escrowed, share)?;
+            Ok(())
+        }
+
+        /// Returns whether `who` is selected by an airdrop drawn with `seed`,
+        /// each account being selected with a chance of `probability_bps` in
+        /// 10 000. The draw is the Blake2x256 hash of `(who, seed)`, so anyone
+        /// can reproduce it off-chain.
+        #[ink(message)]
+        pub fn is_airdrop_winner(&self, who: AccountId, seed: u64, probability_bps: u16) -> bool {
+            let mut hash = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_encoded::<Blake2x256, _>(&(who, seed), &mut hash);
+            let draw = u64::from_le_bytes([
+                hash[0], hash[1], hash[2], hash[3], hash[4], hash[5], hash[6], hash[7],
+            ]);
+            draw % u64::from(BPS_DENOMINATOR) < u64::from(probability_bps)
+        }
+
+        fn set_balance(&mut self, owner: &AccountId, balance: Balance) -> Result<()> {
+            self.update_account_snapshot(owner);
+            let previous = self.balance_of_impl(owner);
+            let delegate = self.delegates.get(owner);
+            if balance > previous {
+                self.move_votes(None, delegate, balance - previous)?;
+            } else {
+                self.move_votes(delegate, None, previous - balance)?;
+            }
+            if balance > 0 && !self.seen.contains(owner) {
+                self.seen.insert(owner, &true);
+                self.total_accounts_ever = self
+                    .total_accounts_ever
+                    .checked_add(1)
+                    .ok_or(Error::ArithmeticOverflow)?;
+            }
+            self.holder_count = match (previous > 0, balance > 0) {
+                (false, true) => self.holder_count.checked_add(1),
+                (true, false) => self.holder_count.checked_sub(1),
+                _ => Some(self.holder_count),
+            }
+            .ok_or(Error::ArithmeticOverflow)?;
+            if self.is_excluded(*owner) {
+                self.excluded_balance_total =
+                    Self::add(Self::sub(self.excluded_balance_total, previous)?, balance)?;
+            }
+            self.balances.insert(owner, &balance);
+            Ok(())
+        }
+
+        fn add(a: Balance, b: Balance) -> Result<Balance> {
+            a.checked_add(b).ok_or(Error::ArithmeticOverflow)
+        }
+
+        fn sub(a: Balance, b: Balance) -> Result<Balance> {
+            a.checked_sub(b).ok_or(Error::ArithmeticOverflow)
+        }
+
+        fn update_account_snapshot(&mut self, account: &AccountId) {
+            let current_id = self.current_snapshot_id;
+            if current_id == 0 {
+                return;
+            }
+            let mut snapshots = self.account_snapshots.get(account).unwrap_or_default();
+            if snapshots.last().is_none_or(|(id, _)| *id < current_id) {
+                snapshots.push((current_id, self.balance_of_impl(account)));
+                self.account_snapshots.insert(account, &snapshots);
+            }
+        }
+
+        #[ink(message)]
+        pub fn fee_bps(&self) -> u16 {
+            self.fee_bps
+        }
+
+        #[ink(message)]
+        pub fn treasury(&self) -> AccountId {
+            self.treasury
+        }
+
+        /// Sets the share of every transfer, in basis points, that is routed to
+        /// `treasury` instead of the recipient.
+        #[ink(message)]
+        pub fn set_fee(&mut self, fee_bps: u16, treasury: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            if fee_bps > BPS_DENOMINATOR {
+                return Err(Error::InvalidFee);
+            }
+            self.fee_bps = fee_bps;
+            self.treasury = treasury;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_fee_exempt(&self, who: AccountId) -> bool {
+            self.fee_exempt.contains(who)
+        }
+
+        /// Exempts transfers from or to `who` (e.g. a DEX pool) from the
+        /// transfer fee. Only callable by the owner.
+        #[ink(message)]
+        pub fn set_fee_exempt(&mut self, who: AccountId, exempt: bool) -> Result<()> {
+            self.ensure_owner()?;
+            if exempt {
+                self.fee_exempt.insert(who, &true);
+            } else {
+                self.fee_exempt.remove(who);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn rounding(&self) -> RoundingMode {
+            self.rounding
+        }
+
+        /// Sets how the transfer fee is rounded. Only callable by the owner.
+        #[ink(message)]
+        pub fn set_rounding(&mut self, rounding: RoundingMode) -> Result<()> {
+            self.ensure_owner()?;
+            self.rounding = rounding;
+            Ok(())
+        }
+
+        fn transfer_fee(&self, value: Balance) -> Balance {
+            Self::bps_of_rounded(value, self.fee_bps, self.rounding)
+        }
+
+        /// Returns what the recipient of a transfer of `value` gets after the
+        /// transfer fee.
+        #[ink(message)]
+        pub fn amount_received(&self, value: Balance) -> Result<Balance> {
+            Self::sub(value, self.transfer_fee(value))
+        }
+
+        /// Returns the smallest amount to transfer so that the recipient gets
+        /// at least `desired_received` after the transfer fee, or
+        /// `Balance::MAX` if no amount can deliver it.
+        #[ink(message)]
+        pub fn amount_to_send(&self, desired_received: Balance) -> Balance {
+            if desired_received == 0 {
+                return 0;
+            }
+            let denominator = Balance::from(BPS_DENOMINATOR);
+            let kept = denominator - Balance::from(self.fee_bps);
+            if kept == 0 {
+                return Balance::MAX;
+            }
+            // With the fee rounded using `offset`, the recipient gets
+            // `ceil((gross * kept - offset) / denominator)`; the smallest gross
+            // reaching `desired_received` is
+            // `floor(((desired_received - 1) * denominator + offset) / kept) + 1`.
+            let offset = self.rounding.offset(denominator);
+            let net = desired_received - 1;
+            (net / kept)
+                .checked_mul(denominator)
+                .and_then(|whole| whole.checked_add((net % kept * denominator + offset) / kept))
+                .and_then(|gross| gross.checked_add(1))
+                .unwrap_or(Balance::MAX)
+        }
+
+        fn bps_of(value: Balance, bps: u16) -> Balance {
+            Self::bps_of_rounded(value, bps, RoundingMode::Down)
+        }
+
+        fn bps_of_rounded(value: Balance, bps: u16, rounding: RoundingMode) -> Balance {
+            let bps = Balance::from(bps);
+            let denominator = Balance::from(BPS_DENOMINATOR);
+            // Split the multiplication so that `value * bps` cannot overflow.
+            let remainder = value % denominator * bps + rounding.offset(denominator);
+            value / denominator * bps + remainder / denominator
+        }
+
+        /// Returns the fee, burned on top of the principal, for flash
+        /// borrowing `amount`.
+        #[ink(message)]
+        pub fn flash_fee(&self, amount: Balance) -> Balance {
+            Self::bps_of(amount, self.flash_fee_bps)
+        }
+
+        #[ink(message)]
+        pub fn set_flash_fee(&mut self, fee_bps: u16) -> Result<()> {
+            self.ensure_owner()?;
+            if fee_bps > BPS_DENOMINATOR {
+                return Err(Error::InvalidFee);
+            }
+            self.flash_fee_bps = fee_bps;
+            Ok(())
+        }
+
+        /// Mints `amount` to `receiver`, calls its `FlashBorrower::on_flash_loan`
+        /// and burns `amount` plus `flash_fee(amount)` from it afterwards.
+        ///
+        /// The borrower may reenter to transfer, swap or otherwise use the
+        /// loan before repaying, but not take out another one. If the callback
+        /// fails or the receiver cannot repay, the returned error reverts the
+        /// whole loan.
+        #[ink(message)]
+        pub fn flash_loan(&mut self, receiver: AccountId, amount: Balance, data: Vec<u8>) -> Result<()> {
+            self.ensure_not_in_callback()?;
+            self.ensure_not_paused(PAUSE_MINT)?;
+            let amount = Self::validate_amount(amount)?;
+            let fee = self.flash_fee(amount);
+            let repayment = amount.checked_add(fee).ok_or(Error::SupplyOverflow)?;
+            self.mint_impl(&receiver, amount)?;
+            // The borrower may move the loaned tokens before repaying.
+            let result = self.with_callback(|_| {
+                build_call::<Environment>()
+                    .call(receiver)
+                    .gas_limit(0)
+                    .call_flags(CallFlags::default().set_allow_reentry(true))
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ON_FLASH_LOAN))
+                            .push_arg(amount)
+                            .push_arg(fee)
+                            .push_arg(data),
+                    )
+                    .returns::<[u8; 4]>()
+                    .try_invoke()
+            })?;
+            if !matches!(result, Ok(Ok(accepted)) if accepted == ON_FLASH_LOAN) {
+                return Err(Error::CallbackFailed);
+            }
+            self.burn_impl(&receiver, repayment)?;
+            // The principal was minted by this very call; only the fee counts
+            // towards `total_burned`.
+            self.total_burned = Self::sub(self.total_burned, amount)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn min_balance(&self) -> Balance {
+            self.min_balance
+        }
+
+        /// Rejects transfers leaving the sender with a non-zero balance below
+        /// `min_balance`; senders can still empty their account.
+        #[ink(message)]
+        pub fn set_min_balance(&mut self, min_balance: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.min_balance = min_balance;
+            Ok(())
+        }
+
+        /// Returns `(max_transfer_per_window, window_secs)`; a zero window means
+        /// transfers are not rate limited.
+        #[ink(message)]
+        pub fn transfer_limit(&self) -> (Balance, u64) {
+            (self.max_transfer_per_window, self.window_secs)
+        }
+
+        /// Limits every account to sending at most `max_transfer_per_window`
+        /// tokens per `window_secs` seconds. A zero window lifts the limit.
+        #[ink(message)]
+        pub fn set_transfer_limit(
+            &mut self,
+            max_transfer_per_window: Balance,
+            window_secs: u64,
+        ) -> Result<()> {
+            self.ensure_owner()?;
+            self.max_transfer_per_window = max_transfer_per_window;
+            self.window_secs = window_secs;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn transfer_cooldown_secs(&self) -> u64 {
+            self.transfer_cooldown_secs
+        }
+
+        /// Makes senders wait `secs` seconds between transfers. Zero disables
+        /// the cooldown.
+        #[ink(message)]
+        pub fn set_transfer_cooldown(&mut self, secs: u64) -> Result<()> {
+            self.ensure_owner()?;
+            self.transfer_cooldown_secs = secs;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn low_balance_threshold(&self) -> Balance {
+            self.low_balance_threshold
+        }
+
+        /// Emits `LowBalanceWarning` whenever a transfer takes the sender's
+        /// balance from at least `threshold` to below it. Zero disables the
+        /// warning.
+        #[ink(message)]
+        pub fn set_low_balance_threshold(&mut self, threshold: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            self.low_balance_threshold = threshold;
+            Ok(())
+        }
+
+        /// Fails with `Error::Cooldown` if `from` transferred within the
+        /// cooldown, and otherwise records the current transfer.
+        fn enforce_transfer_cooldown(&mut self, from: &AccountId) -> Result<()> {
+            if self.transfer_cooldown_secs == 0 {
+                return Ok(());
+            }
+            let now = self.env().block_timestamp();
+            // Block timestamps are in milliseconds.
+            let cooldown = self.transfer_cooldown_secs.saturating_mul(1000);
+            if self
+                .last_transfer
+                .get(from)
+                .is_some_and(|last| now.saturating_sub(last) < cooldown)
+            {
+                return Err(Error::Cooldown);
+            }
+            self.last_transfer.insert(from, &now);
+            Ok(())
+        }
+
+        /// Returns how much `who` can still send in the current window and
+        /// the seconds, rounded up, until the window resets. Without a
+        /// transfer limit this is `(Balance::MAX, 0)`; without an open window
+        /// it is the full limit and `0`.
+        #[ink(message)]
+        pub fn transfer_quota(&self, who: AccountId) -> (Balance, u64) {
+            if self.window_secs == 0 {
+                return (Balance::MAX, 0);
+            }
+            let now = self.env().block_timestamp();
+            // Block timestamps are in milliseconds.
+            let window = self.window_secs.saturating_mul(1000);
+            match self.transfer_windows.get(who) {
+                Some((start, spent)) if now.saturating_sub(start) < window => {
+                    let remaining_ms = window - now.saturating_sub(start);
+                    (
+                        self.max_transfer_per_window.saturating_sub(spent),
+                        remaining_ms.div_ceil(1000),
+                    )
+                }
+                _ => (self.max_transfer_per_window, 0),
+            }
+        }
+
+        /// Charges `value` against `from`'s quota for the current window,
+        /// starting a new window once the previous one has elapsed.
+        fn spend_transfer_quota(&mut self, from: &AccountId, value: Balance) -> Result<()> {
+            if self.window_secs == 0 {
+                return Ok(());
+            }
+            let now = self.env().block_timestamp();
+            // Block timestamps are in milliseconds.
+            let window = self.window_secs.saturating_mul(1000);
+            let (start, spent) = match self.transfer_windows.get(from) {
+                Some((start, spent)) if now.saturating_sub(start) < window => (start, spent),
+                _ => (now, 0),
+            };
+            let spent = spent
+                .checked_add(value)
+                .filter(|spent| *spent <= self.max_transfer_per_window)
+                .ok_or(Error::TransferLimitExceeded)?;
+            self.transfer_windows.insert(from, &(start, spent));
+            Ok(())
+        }
+
+        /// Locks `amount` of the caller's tokens in the contract, vesting
+        /// linearly to `beneficiary` over `duration` milliseconds from `start`.
+        #[ink(message)]
+        pub fn create_vesting(
+            &mut self,
+            beneficiary: AccountId,
+            amount: Balance,
+            start: u64,
+            duration: u64,
+        ) -> Result<()> {
+            self.ensure_owner()?;
+            self.ensure_not_paused(PAUSE_TRANSFER)?;
+            if self.vesting.contains(beneficiary) {
+                return Err(Error::VestingExists);
+            }
+            let caller = self.env().caller();
+            let contract = self.env().account_id();
+            self.transfer_from_to(&caller, &contract, amount, None)?;
+            self.vesting.insert(beneficiary, &(amount, start, duration));
+            self.escrowed = Self::add(self.escrowed, amount)?;
+            Ok(())
+        }
+
+        /// Returns how much of `who`'s vesting schedule has vested so far,
+        /// including any amount already released.
+        #[ink(message)]
+        pub fn vested_amount(&self, who: AccountId) -> Balance {
+            let Some((amount, start, duration)) = self.vesting.get(who) else {
+                return 0;
+            };
+            let now = self.env().block_timestamp();
+            if now < start {
+                return 0;
+            }
+            let elapsed = now - start;
+            if elapsed >= duration {
+                return amount;
+            }
+            let (elapsed, duration) = (Balance::from(elapsed), Balance::from(duration));
+            // Split the multiplication so that `amount * elapsed` cannot overflow.
+            amount / duration * elapsed + amount % duration * elapsed / duration
+        }
+
+        /// Transfers the caller's vested but not yet released tokens to them.
+        #[ink(message)]
+        pub fn release(&mut self) -> Result<()> {
+            self.ensure_not_paused(PAUSE_TRANSFER)?;
+            let caller = self.env().caller();
+            if !self.vesting.contains(caller) {
+                return Err(Error::NoVesting);
+            }
+            let released = self.vesting_released.get(caller).unwrap_or_default();
+            let releasable = Self::sub(self.vested_amount(caller), released)?;
+            let contract = self.env().account_id();
+            self.transfer_from_to(&contract, &caller, releasable, None)?;
+            self.vesting_released.insert(caller, &Self::add(released, releasable)?);
+            self.escrowed = Self::sub(self.escrowed, releasable)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn delegates(&self, account: AccountId) -> Option<AccountId> {
+            self.delegates.get(account)
+        }
+
+        /// Delegates the caller's voting power, which always follows their
+        /// current balance, to `delegatee`. Accounts must delegate to
+        /// themselves for their balance to count as votes.
+        #[ink(message)]
+        pub fn delegate(&mut self, delegatee: AccountId) -> Result<()> {
+            let delegator = self.env().caller();
+            let previous = self.delegates.get(delegator);
+            self.delegates.insert(delegator, &delegatee);
+            self.move_votes(previous, Some(delegatee), self.balance_of_impl(&delegator))?;
+            Self::emit_event(DelegateChanged {
+                delegator,
+                from_delegate: previous,
+                to_delegate: delegatee,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_votes(&self, account: AccountId) -> Balance {
+            self.vote_checkpoints
+                .get(account)
+                .and_then(|checkpoints| checkpoints.last().map(|(_, votes)| *votes))
+                .unwrap_or_default()
+        }
+
+        /// Returns the votes `account` had at `timestamp`.
+        ///
+        /// Only the latest `MAX_VOTE_CHECKPOINTS` changes are kept, so lookups
+        /// before the oldest retained checkpoint return 0.
+        #[ink(message)]
+        pub fn get_past_votes(&self, account: AccountId, timestamp: u64) -> Balance {
+            let checkpoints = self.vote_checkpoints.get(account).unwrap_or_default();
+            let index = checkpoints.partition_point(|(at, _)| *at <= timestamp);
+            match index.checked_sub(1) {
+                Some(index) => checkpoints[index].1,
+                None => 0,
+            }
+        }
+
+        fn move_votes(&mut self, from: Option<AccountId>, to: Option<AccountId>, amount: Balance) -> Result<()> {
+            if from == to || amount == 0 {
+                return Ok(());
+            }
+            if let Some(from) = from {
+                let votes = Self::sub(self.get_votes(from), amount)?;
+                self.write_vote_checkpoint(&from, votes);
+            }
+            if let Some(to) = to {
+                let votes = Self::add(self.get_votes(to), amount)?;
+                self.write_vote_checkpoint(&to, votes);
+            }
+            Ok(())
+        }
+
+        fn write_vote_checkpoint(&mut self, delegate: &AccountId, new_votes: Balance) {
+            let now = self.env().block_timestamp();
+            let mut checkpoints = self.vote_checkpoints.get(delegate).unwrap_or_default();
+            let previous_votes = checkpoints.last().map(|(_, votes)| *votes).unwrap_or_default();
+            match checkpoints.last_mut() {
+                Some((at, votes)) if *at == now => *votes = new_votes,
+                _ => checkpoints.push((now, new_votes)),
+            }
+            if checkpoints.len() > MAX_VOTE_CHECKPOINTS {
+                checkpoints.remove(0);
+            }
+            self.vote_checkpoints.insert(delegate, &checkpoints);
+            Self::emit_event(DelegateVotesChanged {
+                delegate: *delegate,
+                previous_votes,
+                new_votes,
+            });
+        }
+
+        /// Transfers `value` tokens to the contract `to` and notifies it through
+        /// `TokenReceiver::on_token_received`.
+        ///
+        /// If the callback fails or does not accept the tokens the returned
+        /// error reverts the transfer.
+        #[ink(message)]
+        pub fn transfer_and_call(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<()> {
+            self.transfer(to, value)?;
+            let from = self.env().caller();
+            if !self.notify_receiver(from, to, value, data) {
+                return Err(Error::CallbackFailed);
+            }
+            Ok(())
+        }
+
+        /// Approves `spender` like `approve` and then notifies it through
+        /// `ApprovalReceiver`, e.g. so a staking contract can pull a deposit in
+        /// the same transaction. The approval is reverted if `spender` does
+        /// not accept it.
+        ///
+        /// The spender may reenter to move tokens, but not to call any other
+        /// state-changing message, see `with_callback`.
+        #[ink(message)]
+        pub fn approve_and_call(&mut self, spender: AccountId, value: Balance, data: Vec<u8>) -> Result<()> {
+            self.ensure_not_in_callback()?;
+            // Only the allowance mappings change before the callback.
+            self.approve(spender, value)?;
+            let owner = self.env().caller();
+            let result = self.with_callback(|_| {
+                build_call::<Environment>()
+                    .call(spender)
+                    .gas_limit(0)
+                    .call_flags(CallFlags::default().set_allow_reentry(true))
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ON_APPROVAL_RECEIVED))
+                            .push_arg(owner)
+                            .push_arg(value)
+                            .push_arg(data),
+                    )
+                    .returns::<[u8; 4]>()
+                    .try_invoke()
+            })?;
+            if !matches!(result, Ok(Ok(accepted)) if accepted == ON_APPROVAL_RECEIVED) {
+                return Err(Error::CallbackFailed);
+            }
+            Ok(())
+        }
+
+        /// Transfers like `transfer` to accounts, but requires contract
+        /// recipients to accept the tokens through `TokenReceiver`.
+        #[ink(message)]
+        pub fn safe_transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<()> {
+            self.transfer(to, value)?;
+            if self.env().is_contract(&to) {
+                let from = self.env().caller();
+                if !self.notify_receiver(from, to, value, data) {
+                    return Err(Error::NonReceiver);
+                }
+            }
+            Ok(())
+        }
+
+        fn notify_receiver(&self, from: AccountId, to: AccountId, value: Balance, data: Vec<u8>) -> bool {
+            let result = build_call::<Environment>()
+                .call(to)
+                .gas_limit(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ON_TOKEN_RECEIVED))
+                        .push_arg(from)
+                        .push_arg(value)
+                        .push_arg(data),
+                )
+                .returns::<[u8; 4]>()
+                .try_invoke();
+            matches!(result, Ok(Ok(accepted)) if accepted == ON_TOKEN_RECEIVED)
+        }
+
+        /// Sweeps this contract's whole balance of the PSP22 `token` to `to`,
+        /// rescuing tokens that were sent to the contract by mistake.
+        #[ink(message)]
+        pub fn reclaim_token(&mut self, token: AccountId, to: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            let amount = build_call::<Environment>()
+                .call(token)
+                .gas_limit(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::balance_of")))
+                        .push_arg(self.env().account_id()),
+                )
+                .returns::<Balance>()
+                .try_invoke()
+                .map_err(|_| Error::TransferFailed)?
+                .map_err(|_| Error::TransferFailed)?;
+            build_call::<Environment>()
+                .call(token)
+                .gas_limit(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer")))
+                        .push_arg(to)
+                        .push_arg(amount),
+                )
+                .returns::<Result<()>>()
+                .try_invoke()
+                .map_err(|_| Error::TransferFailed)?
+                .map_err(|_| Error::TransferFailed)?
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        #[ink(message)]
+        pub fn is_migrated(&self, holder: AccountId) -> bool {
+            self.migrated.contains(holder)
+        }
+
+        /// Returns the previous token holders can `migrate_from`, if any.
+        #[ink(message)]
+        pub fn migration_source(&self) -> Option<AccountId> {
+            self.migration_source
+        }
+
+        /// Sets the PSP22 token holders can `migrate_from`; `None` ends the
+        /// migration. Only callable by minters, as migrating mints supply.
+        #[ink(message)]
+        pub fn set_migration_source(&mut self, old_token: Option<AccountId>) -> Result<()> {
+            self.ensure_role(MINTER_ROLE)?;
+            self.migration_source = old_token;
+            Ok(())
+        }
+
+        /// Swaps the caller's whole balance on `old_token` for the same amount
+        /// of this token, for upgrading holders from a previous token.
+        ///
+        /// The old tokens are pulled into this contract through
+        /// `PSP22::transfer_from`, so `holder` must first approve this contract
+        /// on `old_token` and the tokens cannot be migrated twice. Fails with
+        /// `Error::MigrationNotAllowed` unless `old_token` is the
+        /// `migration_source` and the caller is `holder`. Each holder can only
+        /// migrate once.
+        #[ink(message)]
+        pub fn migrate_from(&mut self, old_token: AccountId, holder: AccountId) -> Result<Balance> {
+            self.ensure_not_paused(PAUSE_MINT)?;
+            if self.migration_source != Some(old_token) || self.env().caller() != holder {
+                return Err(Error::MigrationNotAllowed);
+            }
+            if self.is_migrated(holder) {
+                return Err(Error::AlreadyMigrated);
+            }
+            let amount = build_call::<Environment>()
+                .call(old_token)
+                .gas_limit(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::balance_of")))
+                        .push_arg(holder),
+                )
+                .returns::<Balance>()
+                .try_invoke()
+                .map_err(|_| Error::TransferFailed)?
+                .map_err(|_| Error::TransferFailed)?;
+            if amount > 0 {
+                build_call::<Environment>()
+                    .call(old_token)
+                    .gas_limit(0)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer_from")))
+                            .push_arg(holder)
+                            .push_arg(self.env().account_id())
+                            .push_arg(amount),
+                    )
+                    .returns::<Result<()>>()
+                    .try_invoke()
+                    .map_err(|_| Error::TransferFailed)?
+                    .map_err(|_| Error::TransferFailed)?
+                    .map_err(|_| Error::TransferFailed)?;
+                self.mint_impl(&holder, amount)?;
+            }
+            self.migrated.insert(holder, &true);
+            Ok(amount)
+        }
+
+        /// Sends the contract's native balance to `to`, keeping back what is
+        /// needed to redeem deposited tokens through `withdraw`.
+        #[ink(message)]
+        pub fn sweep_native(&mut self, to: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            let amount = self.env().balance().saturating_sub(self.deposited);
+            if amount == 0 {
+                return Ok(());
+            }
+            self.env()
+                .transfer(to, amount)
+                .map_err(|_| Error::TransferFailed)
+        }
+
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            self.non_reentrant(|erc20| {
+                erc20.ensure_role(MINTER_ROLE)?;
+                erc20.ensure_not_paused(PAUSE_MINT)?;
+                let value = Self::validate_amount(value)?;
+                erc20.mint_impl(&to, value)
+            })
+        }
+
+        fn mint_impl(&mut self, to: &AccountId, value: Balance) -> Result<()> {
+            // Tokens minted to the zero account could never be spent.
+            if *to == AccountId::from([0u8; 32]) {
+                return Err(Error::ZeroAddress);
+            }
+            self.before_token_transfer(None, Some(to), value)?;
+            let total_supply = self
+                .total_supply
+                .checked_add(value)
+                .ok_or(Error::SupplyOverflow)?;
+            if total_supply > self.cap() {
+                return Err(Error::CapExceeded);
+            }
+            let to_balance = Self::add(self.balance_of_impl(to), value)?;
+            self.total_supply = total_supply;
+            self.set_balance(to, to_balance)?;
+            Self::emit_event(Transfer {
+                from: None,
+                to: *to,
+                value,
+                spender: None,
+                seq: self.next_transfer_seq()?,
+            });
+            Ok(())
+        }
+
+        /// Mints tokens 1:1 for the native value attached to the call.
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) -> Result<()> {
+            self.non_reentrant(|erc20| {
+                erc20.ensure_not_paused(PAUSE_MINT)?;
+                let caller = erc20.env().caller();
+                let value = Self::validate_amount(erc20.env().transferred_value())?;
+                erc20.mint_impl(&caller, value)?;
+                erc20.deposited = erc20.deposited.saturating_add(value);
+                Self::emit_event(Deposit {
+                    account: caller,
+                    value,
+                });
+                Ok(())
+            })
+        }
+
+        /// Burns `value` tokens and pays the same amount of native value back.
+        /// Frozen accounts cannot withdraw.
+        ///
+        /// If the native transfer fails the returned error reverts the burn.
+        #[ink(message)]
+        pub fn withdraw(&mut self, value: Balance) -> Result<()> {
+            self.non_reentrant(|erc20| {
+                erc20.ensure_not_paused(PAUSE_BURN)?;
+                let value = Self::validate_amount(value)?;
+                let caller = erc20.env().caller();
+                erc20.burn_impl(&caller, value)?;
+                erc20.deposited = erc20.deposited.saturating_sub(value);
+                erc20
+                    .env()
+                    .transfer(caller, value)
+                    .map_err(|_| Error::TransferFailed)?;
+                Self::emit_event(Withdrawal {
+                    account: caller,
+                    value,
+                });
+                Ok(())
+            })
+        }
+
+        /// Returns the cumulative amount of tokens ever burned, including
+        /// tokens redeemed through `withdraw`.
+        #[ink(message)]
+        pub fn total_burned(&self) -> Balance {
+            self.total_burned
+        }
+
+        #[ink(message)]
+        pub fn burn(&mut self, value: Balance) -> Result<()> {
+            self.non_reentrant(|erc20| {
+                erc20.ensure_not_paused(PAUSE_BURN)?;
+                let caller = erc20.env().caller();
+                let value = Self::validate_amount(value)?;
+                erc20.burn_impl(&caller, value)
+            })
+        }
+
+        #[ink(message)]
+        pub fn burn_from(&mut self, from: AccountId, value: Balance) -> Result<()> {
+            self.ensure_role(MINTER_ROLE)?;
+            self.ensure_not_paused(PAUSE_BURN)?;
+            let value = Self::validate_amount(value)?;
+            let caller = self.env().caller();
+            let allowance = self.allowance_impl(&from, &caller);
+            if allowance < value {
+                return Err(Error::InsufficientAllowance);
+            }
+            self.burn_impl(&from, value)?;
+            self.set_allowance(&from, &caller, Self::sub(allowance, value)?);
+            Ok(())
+        }
+
+        /// Rejects amounts that would make a supply change a no-op.
+        fn validate_amount(value: Balance) -> Result<Balance> {
+            if value == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            Ok(value)
+        }
+
+        /// Burns `value` of the caller's tokens for a relayer to release on
+        /// `dest_chain` to `dest_recipient`.
+        #[ink(message)]
+        pub fn bridge_burn(&mut self, value: Balance, dest_chain: u32, dest_recipient: Vec<u8>) -> Result<()> {
+            if dest_recipient.len() > MAX_BRIDGE_RECIPIENT_LEN {
+                return Err(Error::DataTooLarge);
+            }
+            self.non_reentrant(|erc20| {
+                erc20.ensure_not_paused(PAUSE_BURN)?;
+                let caller = erc20.env().caller();
+                let value = Self::validate_amount(value)?;
+                erc20.burn_impl(&caller, value)?;
+                Self::emit_event(BridgeBurn {
+                    from: caller,
+                    value,
+                    dest_chain,
+                    dest_recipient,
+                });
+                Ok(())
+            })
+        }
+
+        #[ink(message)]
+        pub fn is_bridge_tx_processed(&self, src_tx: [u8; 32]) -> bool {
+            self.processed_bridge_txs.contains(src_tx)
+        }
+
+        /// Mints tokens locked or burned on `src_chain` in transaction `src_tx`.
+        /// Each source transaction can only be minted once.
+        #[ink(message)]
+        pub fn bridge_mint(&mut self, to: AccountId, value: Balance, src_chain: u32, src_tx: [u8; 32]) -> Result<()> {
+            self.non_reentrant(|erc20| {
+                erc20.ensure_role(BRIDGE_ROLE)?;
+                erc20.ensure_not_paused(PAUSE_MINT)?;
+                if erc20.is_bridge_tx_processed(src_tx) {
+                    return Err(Error::AlreadyProcessed);
+                }
+                let value = Self::validate_amount(value)?;
+                erc20.mint_impl(&to, value)?;
+                erc20.processed_bridge_txs.insert(src_tx, &true);
+                Self::emit_event(BridgeMint {
+                    to,
+                    value,
+                    src_chain,
+                    src_tx,
+                });
+                Ok(())
+            })
+        }
+
+        /// Burns `value` of the tokens held by the contract itself, such as
+        /// collected fees. Tokens escrowed for vesting or distributions are
+        /// not available.
+        #[ink(message)]
+        pub fn burn_treasury(&mut self, value: Balance) -> Result<()> {
+            self.ensure_owner()?;
+            let value = Self::validate_amount(value)?;
+            let contract = self.env().account_id();
+            if self.balance_of_impl(&contract).saturating_sub(self.escrowed) < value {
+                return Err(Error::InsufficientBalance);
+            }
+            self.burn_impl(&contract, value)
+        }
+
+        fn burn_impl(&mut self, from: &AccountId, value: Balance) -> Result<()> {
+            self.before_token_transfer(Some(from), None, value)?;
+            // Burning releases value elsewhere, e.g. on another chain through
+            // `bridge_burn`, so it is subject to freezes and the allowlist.
+            self.ensure_not_frozen(from)?;
+            self.ensure_allowed(from)?;
+            let from_balance = self.balance_of_impl(from);
+            if from_balance < value {
+                return Err(Error::InsufficientBalance);
+            }
+            self.ensure_unlocked(from, from_balance - value)?;
+            let total_burned = self
+                .total_burned
+                .checked_add(value)
+                .ok_or(Error::SupplyOverflow)?;
+            let total_supply = Self::sub(self.total_supply, value)?;
+            self.set_balance(from, from_balance - value)?;
+            self.total_supply = total_supply;
+            self.total_burned = total_burned;
+            Self::emit_event(Burn {
+                from: *from,
+                value,
+            });
+            Ok(())
+        }
+
+        /// Packs an `(owner, spender)` pair into a single 32-byte storage key.
+        ///
+        /// The tuple key encodes to 64 bytes; hashing it halves the key that
+        /// ends up in the storage trie and in every storage proof.
+        #[inline]
+        fn allowance_key(owner: &AccountId, spender: &AccountId) -> AllowanceKey {
+            let mut key = AllowanceKey::default();
+            ink::env::hash_encoded::<Blake2x256, _>(&(owner, spender), &mut key);
+            key
+        }
+
+        /// Expired allowances read as zero everywhere, including `transfer_from`.
+        #[inline]
+        fn allowance_impl(&self, owner: &AccountId, spender: &AccountId) -> Balance {
+            let (allowance, expires_at) = self
+                .allowances
+                .get(Self::allowance_key(owner, spender))
+                .unwrap_or_default();
+            if expires_at.is_some_and(|expires_at| self.env().block_timestamp() > expires_at) {
+                return 0;
+            }
+            allowance
+        }
+
+        /// Returns whether `spender` may move `owner`'s tokens without limit,
+        /// i.e. the allowance is `Balance::MAX` and `transfer_from` never
+        /// decrements it.
+        #[ink(message)]
+        pub fn is_unlimited_allowance(&self, owner: AccountId, spender: AccountId) -> bool {
+            self.allowance_impl(&owner, &spender) == Balance::MAX
+        }
+
+        /// Returns the timestamp after which `spender`'s allowance over
+        /// `owner`'s tokens reads as zero, if it was set with an expiry.
+        #[ink(message)]
+        pub fn allowance_expiry(&self, owner: AccountId, spender: AccountId) -> Option<u64> {
+            self.allowances
+                .get(Self::allowance_key(&owner, &spender))
+                .and_then(|(_, expires_at)| expires_at)
+        }
+
+        /// Approves like `approve`, but the allowance lapses once the block
+        /// timestamp passes `expires_at`. `increase_allowance` and
+        /// `decrease_allowance` keep the expiry; `approve` clears it.
+        #[ink(message)]
+        pub fn approve_with_expiry(&mut self, spender: AccountId, value: Balance, expires_at: u64) -> Result<()> {
+            let owner = self.env().caller();
+            self.ensure_not_frozen(&owner)?;
+            self.ensure_not_frozen(&spender)?;
+            if value > 0 {
+                self.ensure_not_paused(PAUSE_APPROVE)?;
+            }
+            self.ensure_allowance_within_max(value)?;
+            self.write_allowance(&owner, &spender, value, Some(expires_at));
+            Ok(())
+        }
+
+        /// Returns every spender `owner` has a non-zero allowance for, together
+        /// with the allowance.
+        #[ink(message)]
+        pub fn allowances_of(&self, owner: AccountId) -> Vec<(AccountId, Balance)> {
+            self.spenders
+                .get(owner)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|spender| (spender, self.allowance_impl(&owner, &spender)))
+                .filter(|(_, allowance)| *allowance > 0)
+                .collect()
+        }
+
+        /// Returns the allowance of every `(owner, spender)` pair in `queries`,
+        /// in input order.
+        #[ink(message)]
+        pub fn allowances_batch(&self, queries: Vec<(AccountId, AccountId)>) -> Vec<Balance> {
+            queries
+                .iter()
+                .map(|(owner, spender)| self.allowance_impl(owner, spender))
+                .collect()
+        }
+
+        /// Like `decrease_allowance`, but clamps the allowance at zero instead
+        /// of failing when `delta` exceeds it.
+        #[ink(message)]
+        pub fn decrease_allowance_saturating(&mut self, spender: AccountId, delta: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            let allowance = self.allowance_impl(&owner, &spender).saturating_sub(delta);
+            self.set_allowance(&owner, &spender, allowance);
+            Ok(())
+        }
+
+        /// Returns the tokens held by the contract's own account, i.e. the
+        /// treasury including any escrow.
+        #[ink(message)]
+        pub fn self_balance(&self) -> Balance {
+            self.balance_of_impl(&self.env().account_id())
+        }
+
+        /// Returns the pooled balance of `a` and `b`, saturating at
+        /// `Balance::MAX`. Passing the same account twice counts it twice.
+        #[ink(message)]
+        pub fn combined_balance(&self, a: AccountId, b: AccountId) -> Balance {
+            self.balance_of_impl(&a).saturating_add(self.balance_of_impl(&b))
+        }
+
+        /// Returns `owner`'s balance and `spender`'s allowance over it in one call.
+        #[ink(message)]
+        pub fn balance_and_allowance(&self, owner: AccountId, spender: AccountId) -> (Balance, Balance) {
+            (
+                self.balance_of_impl(&owner),
+                self.allowance_impl(&owner, &spender),
+            )
+        }
+
+        /// Returns the amount `spender` can move from `owner` right now, i.e.
+        /// the allowance capped by the owner's balance.
+        #[ink(message)]
+        pub fn spendable(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowance_impl(&owner, &spender)
+                .min(self.balance_of_impl(&owner))
+        }
+
+        /// Returns the largest allowance `approve`, `increase_allowance` and
+        /// `permit` may grant, `None` if unlimited.
+        #[ink(message)]
+        pub fn max_allowance(&self) -> Option<Balance> {
+            self.max_allowance
+        }
+
+        #[ink(message)]
+        pub fn set_max_allowance(&mut self, max_allowance: Option<Balance>) -> Result<()> {
+            self.ensure_owner()?;
+            self.max_allowance = max_allowance;
+            Ok(())
+        }
+
+        fn ensure_allowance_within_max(&self, value: Balance) -> Result<()> {
+            if self.max_allowance.is_some_and(|max| value > max) {
+                return Err(Error::AllowanceTooHigh);
+            }
+            Ok(())
+        }
+
+        /// Sets the allowance amount, keeping its expiry unless it has already
+        /// passed, in which case the new amount does not expire.
+        fn set_allowance(&mut self, owner: &AccountId, spender: &AccountId, value: Balance) {
+            let now = self.env().block_timestamp();
+            let expires_at = self
+                .allowance_expiry(*owner, *spender)
+                .filter(|expires_at| now <= *expires_at);
+            self.write_allowance(owner, spender, value, expires_at);
+        }
+
+        /// Stores an allowance, keeps the owner's spender list in sync and
+        /// emits the `Approval` event every allowance change goes through.
+        fn write_allowance(
+            &mut self,
+            owner: &AccountId,
+            spender: &AccountId,
+            value: Balance,
+            expires_at: Option<u64>,
+        ) {
+            let mut spenders = self.spenders.get(owner).unwrap_or_default();
+            let position = spenders.iter().position(|s| s == spender);
+            match (position, value > 0) {
+                (None, true) => spenders.push(*spender),
+                (Some(index), false) => {
+                    spenders.swap_remove(index);
+                }
+                _ => {}
+            }
+            if spenders.is_empty() {
+                self.spenders.remove(owner);
+            } else {
+                self.spenders.insert(owner, &spenders);
+            }
+            self.allowances
+                .insert(Self::allowance_key(owner, spender), &(value, expires_at));
+            Self::emit_event(Approval {
+                from: *owner,
+                to: *spender,
+                value,
+            });
+        }
+
+        /// Hook invoked before any balance movement.
+        ///
+        /// Minting passes `from: None` and burning passes `to: None`; regular
+        /// transfers pass both accounts. Forks can add checks that should apply
+        /// to every entry point here, returning an error to abort the movement.
+        fn before_token_transfer(
+            &mut self,
+            _from: Option<&AccountId>,
+            _to: Option<&AccountId>,
+            _value: Balance,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        /// Emits `event`, or does nothing when built with the `no-events`
+        /// feature.
+        fn emit_event<E>(event: E)
+        where
+            E: Into<<Erc20 as ink::reflect::ContractEventBase>::Type>,
+        {
+            #[cfg(not(feature = "no-events"))]
+            Self::env().emit_event(event);
+            #[cfg(feature = "no-events")]
+            let _ = event;
+        }
+
+        /// Returns the `seq` of the next `Transfer` event and advances it.
+        fn next_transfer_seq(&mut self) -> Result<u64> {
+            let seq = self.transfer_seq;
+            self.transfer_seq = seq.checked_add(1).ok_or(Error::ArithmeticOverflow)?;
+            Ok(seq)
+        }
+
+        fn emit_transfer_meta(&self, from: &AccountId, to: &AccountId, value: Balance) {
+            Self::emit_event(TransferMeta {
+                token_id: self.token_id,
+                from: *from,
+                to: *to,
+                value,
+            });
+        }
+
+        fn transfer_from_to(
+            &mut self,
+            from: &AccountId,
+            to: &AccountId,
+            value: Balance,
+            spender: Option<AccountId>,
+        ) -> Result<()> {
+            self.before_token_transfer(Some(from), Some(to), value)?;
+            self.ensure_not_frozen(from)?;
+            self.ensure_not_frozen(to)?;
+            self.ensure_allowed(from)?;
+            self.ensure_allowed(to)?;
+            let from_balance = self
+                .balance_of_impl(from)
+                .checked_sub(value)
+                .ok_or(Error::InsufficientBalance)?;
+            self.ensure_unlocked(from, from_balance)?;
+            if from == to || value == 0 {
+                return self.move_tokens(from, to, value, spender, 0);
+            }
+            let contract = self.env().account_id();
+            if *from != contract && from_balance > 0 && from_balance < self.min_balance {
+                return Err(Error::DustBalance);
+            }
+            // Moves into and out of the contract's own escrow and of exempt
+            // accounts are not charged.
+            let fee = if *from == contract
+                || *to == contract
+                || self.is_fee_exempt(*from)
+                || self.is_fee_exempt(*to)
+            {
+                0
+            } else {
+                self.transfer_fee(value)
+            };
+            if *from != contract {
+                self.enforce_transfer_cooldown(from)?;
+                self.spend_transfer_quota(from, value)?;
+            }
+            self.move_tokens(from, to, value, spender, fee)
+        }
+
+        /// Moves `value` from `from` to `to`, routing `fee` of it to the
+        /// treasury. Only balances are checked; freezes, the allowlist, locks
+        /// and the other account-level restrictions are up to the caller.
+        fn move_tokens(
+            &mut self,
+            from: &AccountId,
+            to: &AccountId,
+            value: Balance,
+            spender: Option<AccountId>,
+            fee: Balance,
+        ) -> Result<()> {
+            let from_balance = self
+                .balance_of_impl(from)
+                .checked_sub(value)
+                .ok_or(Error::InsufficientBalance)?;
+            // Self-transfers and zero-value transfers leave every balance as it
+            // is, so only the events are emitted.
+            if from == to || value == 0 {
+                self.emit_transfer_meta(from, to, value);
+                Self::emit_event(Transfer {
+                    from: Some(*from),
+                    to: *to,
+                    value,
+                    spender,
+                    seq: self.next_transfer_seq()?,
+                });
+                return Ok(());
+            }
+            let received = Self::sub(value, fee)?;
+            let to_balance = self
+                .balance_of_impl(to)
+                .checked_add(received)
+                .ok_or(Error::BalanceOverflow)?;
+            let crossed_low_balance = from_balance < self.low_balance_threshold
+                && self.balance_of_impl(from) >= self.low_balance_threshold;
+            self.set_balance(from, from_balance)?;
+            self.set_balance(to, to_balance)?;
+            self.emit_transfer_meta(from, to, value);
+            Self::emit_event(Transfer {
+                from: Some(*from),
+                to: *to,
+                value: received,
+                spender,
+                seq: self.next_transfer_seq()?,
+            });
+            if fee > 0 {
+                let treasury = self.treasury;
+                let treasury_balance = self
+                    .balance_of_impl(&treasury)
+                    .checked_add(fee)
+                    .ok_or(Error::BalanceOverflow)?;
+                self.set_balance(&treasury, treasury_balance)?;
+                Self::emit_event(Transfer {
+                    from: Some(*from),
+                    to: treasury,
+                    value: fee,
+                    spender,
+                    seq: self.next_transfer_seq()?,
+                });
+            }
+            if crossed_low_balance {
+                Self::emit_event(LowBalanceWarning {
+                    account: *from,
+                    balance: from_balance,
+                });
+            }
+            Ok(())
+        }
+    }
+
+
+    impl PSP22 for Erc20 {
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balance_of_impl(&owner)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowance_impl(&owner, &spender)
+        }
+
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            self.non_reentrant_transfer(|erc20| {
+                erc20.ensure_not_paused(PAUSE_TRANSFER)?;
+                let caller = erc20.env().caller();
+                erc20.transfer_from_to(&caller, &to, value, None)
+            })
+        }
+
+        #[ink(message)]
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<()> {
+            self.non_reentrant_transfer(|erc20| {
+                erc20.ensure_not_paused(PAUSE_TRANSFER_FROM)?;
+                let caller = erc20.env().caller();
+                let allowance = erc20.allowance_impl(&from, &caller);
+                if allowance < value {
+                    return Err(Error::InsufficientAllowance);
+                }
+                // An allowance of `Balance::MAX` is treated as unlimited and never decremented.
+                if allowance != Balance::MAX {
+                    erc20.set_allowance(&from, &caller, Self::sub(allowance, value)?);
+                }
+                erc20.transfer_from_to(&from, &to, value, Some(caller))
+            })
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            self.ensure_not_frozen(&owner)?;
+            self.ensure_not_frozen(&spender)?;
+            // Re-approving the current allowance needs no write and no event.
+            let current = self
+                .allowances
+                .get(Self::allowance_key(&owner, &spender))
+                .unwrap_or_default();
+            if current == (value, None) {
+                return Ok(());
+            }
+            if value > 0 {
+                self.ensure_not_paused(PAUSE_APPROVE)?;
+            }
+            self.ensure_allowance_within_max(value)?;
+            self.write_allowance(&owner, &spender, value, None);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta: Balance) -> Result<()> {
+            self.ensure_not_paused(PAUSE_APPROVE)?;
+            let owner = self.env().caller();
+            let allowance = self
+                .allowance_impl(&owner, &spender)
+                .checked_add(delta)
+                .ok_or(Error::AllowanceOverflow)?;
+            self.ensure_allowance_within_max(allowance)?;
+            self.set_allowance(&owner, &spender, allowance);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta: Balance) -> Result<()> {
+            let owner = self.env().caller();
+            let allowance = self
+                .allowance_impl(&owner, &spender)
+                .checked_sub(delta)
+                .ok_or(Error::InsufficientAllowance)?;
+            self.set_allowance(&owner, &spender, allowance);
+            Ok(())
+        }
+    }
+
+
+    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
+    /// module and test functions are marked with a `#[test]` attribute.
+    /// The below code is technically just normal Rust code.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        type Event = <Erc20 as ::ink::reflect::ContractEventBase>::Type;
+
+        fn recorded_events() -> Vec<Event> {
+            ink::env::test::recorded_events()
+                .map(|event| {
+                    <Event as scale::Decode>::decode(&mut &event.data[..])
+                        .expect("encountered invalid contract event data buffer")
+                })
+                .collect()
+        }
+
+        #[ink::test]
+        fn test_all() {
+            let total_supply = 1000000000;
+            let mut erc20 = Erc20::new(1000000000);
+            assert_eq!(total_supply, erc20.total_supply());
+
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            // Alice owns all the tokens on contract instantiation
+            assert_eq!(erc20.balance_of(accounts.alice), total_supply);
+            // Bob does not owns tokens
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), total_supply - 100);
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+
+            assert_eq!(erc20.approve(accounts.charlie, 5000), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.charlie), 5000);
+
+
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(contract);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.bob, 1000), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 1100);
+            assert_eq!(erc20.balance_of(accounts.alice), total_supply -100 -1000);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.charlie), 4000);
+        }
+
+        #[ink::test]
+        fn new_uses_default_metadata() {
+            let erc20 = Erc20::new(1000);
+            assert_eq!(erc20.name(), "my-token");
+            assert_eq!(erc20.symbol(), "BTCF");
+            assert_eq!(erc20.decimals(), 8);
+        }
+
+        #[ink::test]
+        fn default_has_zero_supply() {
+            let erc20 = Erc20::default();
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.total_supply(), 0);
+            assert_eq!(erc20.balance_of(accounts.alice), 0);
+            assert_eq!(erc20.holder_count(), 0);
+            assert_eq!(erc20.owner(), accounts.alice);
+        }
+
+        #[ink::test]
+        fn new_with_metadata_works() {
+            let erc20 = Erc20::new_with_metadata(1000, "Token".to_string(), "TKN".to_string(), 12)
+                .expect("valid metadata");
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.name(), "Token");
+            assert_eq!(erc20.symbol(), "TKN");
+            assert_eq!(erc20.decimals(), 12);
+            assert_eq!(erc20.balance_of(accounts.alice), 1000);
+        }
+
+        #[ink::test]
+        fn force_transfer_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.transfer(accounts.bob, 300), Ok(()));
+
+            assert_eq!(erc20.force_transfer(accounts.bob, accounts.charlie, 200), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+            assert_eq!(erc20.balance_of(accounts.charlie), 200);
+            #[cfg(not(feature = "no-events"))]
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::ForcedTransfer(ForcedTransfer { from, to, value: 200 }))
+                    if *from == accounts.bob && *to == accounts.charlie
+            ));
+            assert_eq!(
+                erc20.force_transfer(accounts.bob, accounts.charlie, 101),
+                Err(Error::InsufficientBalance)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                erc20.force_transfer(accounts.bob, accounts.charlie, 100),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+        }
+
+        #[ink::test]
+        fn force_transfer_skips_account_restrictions() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.transfer(accounts.bob, 300), Ok(()));
+            assert_eq!(erc20.set_fee(1000, accounts.django), Ok(()));
+            assert_eq!(erc20.set_transfer_limit(10, 60), Ok(()));
+            assert_eq!(erc20.set_transfer_cooldown(60), Ok(()));
+            assert_eq!(erc20.set_allowlist_enabled(true), Ok(()));
+            assert_eq!(erc20.freeze(accounts.bob), Ok(()));
+
+            assert_eq!(erc20.force_transfer(accounts.bob, accounts.charlie, 200), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+            assert_eq!(erc20.balance_of(accounts.charlie), 200);
+            assert_eq!(erc20.balance_of(accounts.django), 0);
+            // Neither the quota nor the cooldown of the seized account was used.
+            assert_eq!(erc20.transfer_quota(accounts.bob), (10, 0));
+            assert!(erc20.last_transfer.get(accounts.bob).is_none());
+        }
+
+        #[ink::test]
+        fn token_info_matches_getters() {
+            let erc20 = Erc20::new_with_metadata(1000, "Token".to_string(), "TKN".to_string(), 12)
+                .expect("valid metadata");
+            let info = erc20.token_info();
+            assert_eq!(info.name, erc20.name());
+            assert_eq!(info.symbol, erc20.symbol());
+            assert_eq!(info.decimals, erc20.decimals());
+            assert_eq!(info.total_supply, erc20.total_supply());
+            assert_eq!(
+                info,
+                TokenInfo {
+                    name: "Token".to_string(),
+                    symbol: "TKN".to_string(),
+                    decimals: 12,
+                    total_supply: 1000,
+                }
+            );
+        }
+
+        #[ink::test]
+        fn token_id_is_derived_from_metadata() {
+            let mut erc20 = Erc20::new_with_metadata(1000, "Token".to_string(), "TKN".to_string(), 12)
+                .expect("valid metadata");
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let token_id = erc20.token_id();
+            assert_eq!(Erc20::metadata_token_id("Token", "TKN"), token_id);
+            assert_ne!(Erc20::metadata_token_id("Token", "TKN2"), token_id);
+
+            // Renaming keeps the id stable.
+            assert_eq!(erc20.set_metadata("Renamed".to_string(), "RNM".to_string()), Ok(()));
+            assert_eq!(erc20.token_id(), token_id);
+
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            #[cfg(not(feature = "no-events"))]
+            let events = recorded_events();
+            #[cfg(not(feature = "no-events"))]
+            assert!(matches!(
+                &events[events.len() - 2],
+                Event::TransferMeta(TransferMeta { token_id: id, to, value: 100, .. })
+                    if *id == token_id && *to == accounts.bob
+            ));
+        }
+
+        #[ink::test]
+        fn set_code_hash_requires_owner() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.set_code_hash([1; 32]), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn set_decimals_requires_zero_supply() {
+            let mut erc20 = Erc20::new(0);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.set_decimals(18), Ok(()));
+            assert_eq!(erc20.decimals(), 18);
+            #[cfg(not(feature = "no-events"))]
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::MetadataUpdated(MetadataUpdated { decimals: 18, .. }))
+            ));
+
+            assert_eq!(erc20.mint(accounts.bob, 1), Ok(()));
+            assert_eq!(erc20.set_decimals(6), Err(Error::SupplyNotZero));
+            assert_eq!(erc20.decimals(), 18);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.set_decimals(6), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn metadata_lengths_are_bounded() {
+            let name = |len| "n".repeat(len);
+            let symbol = |len| "S".repeat(len);
+            assert!(Erc20::new_with_metadata(0, name(MAX_NAME_LEN), symbol(MAX_SYMBOL_LEN), 8).is_ok());
+            assert!(matches!(
+                Erc20::new_with_metadata(0, name(MAX_NAME_LEN + 1), symbol(1), 8),
+                Err(Error::MetadataTooLong)
+            ));
+            assert!(matches!(
+                Erc20::new_with_metadata(0, name(1), symbol(MAX_SYMBOL_LEN + 1), 8),
+                Err(Error::MetadataTooLong)
+            ));
+            assert!(matches!(
+                Erc20::new_with_metadata(0, String::new(), symbol(1), 8),
+                Err(Error::EmptyMetadata)
+            ));
+
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.set_metadata(name(MAX_NAME_LEN), symbol(MAX_SYMBOL_LEN)), Ok(()));
+            assert_eq!(
+                erc20.set_metadata(name(1), symbol(MAX_SYMBOL_LEN + 1)),
+                Err(Error::MetadataTooLong)
+            );
+            assert_eq!(erc20.set_metadata(name(1), String::new()), Err(Error::EmptyMetadata));
+            assert_eq!(erc20.symbol(), symbol(MAX_SYMBOL_LEN));
+        }
+
+        #[ink::test]
+        fn set_metadata_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.set_metadata("my-token".to_string(), "NEW".to_string()), Ok(()));
+            assert_eq!(erc20.name(), "my-token");
+            assert_eq!(erc20.symbol(), "NEW");
+            assert_eq!(erc20.decimals(), 8);
+            #[cfg(not(feature = "no-events"))]
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::MetadataUpdated(MetadataUpdated { symbol, decimals: 8, .. })) if symbol == "NEW"
+            ));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                erc20.set_metadata("x".to_string(), "X".to_string()),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(erc20.symbol(), "NEW");
+        }
+
+        #[ink::test]
+        fn balances_of_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.transfer(accounts.charlie, 200), Ok(()));
+            assert_eq!(erc20.transfer(accounts.eve, 300), Ok(()));
+
+            assert_eq!(
+                erc20.balances_of(vec![
+                    accounts.alice,
+                    accounts.bob,
+                    accounts.charlie,
+                    accounts.django,
+                    accounts.eve,
+                ]),
+                vec![400, 100, 200, 0, 300]
+            );
+        }
+
+        #[ink::test]
+        fn account_has_balance_distinguishes_drained_accounts() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert!(erc20.account_has_balance(accounts.alice));
+            assert!(!erc20.account_has_balance(accounts.bob));
+
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer(accounts.alice, 100), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert!(erc20.account_has_balance(accounts.bob));
+            assert_eq!(erc20.balance_of(accounts.charlie), 0);
+            assert!(!erc20.account_has_balance(accounts.charlie));
+        }
+
+        #[ink::test]
+        fn zero_supply_deploy_credits_no_balance() {
+            let erc20 = Erc20::new(0);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert!(!erc20.account_has_balance(accounts.alice));
+        }
+
+        #[ink::test]
+        fn holder_count_tracks_non_zero_balances() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.holder_count(), 1);
+
+            assert_eq!(erc20.transfer(accounts.bob, 0), Ok(()));
+            assert_eq!(erc20.transfer(accounts.alice, 100), Ok(()));
+            assert_eq!(erc20.holder_count(), 1);
+            assert_eq!(erc20.transfer(accounts.bob, 400), Ok(()));
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.holder_count(), 2);
+            assert_eq!(erc20.mint(accounts.charlie, 100), Ok(()));
+            assert_eq!(erc20.holder_count(), 3);
+
+            // Alice sends her entire balance away and stops being a holder.
+            assert_eq!(erc20.transfer(accounts.bob, 500), Ok(()));
+            assert_eq!(erc20.holder_count(), 2);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(erc20.burn(100), Ok(()));
+            assert_eq!(erc20.holder_count(), 1);
+        }
+
+        #[ink::test]
+        fn total_accounts_ever_counts_each_account_once() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.total_accounts_ever(), 1);
+
+            assert_eq!(erc20.transfer(accounts.bob, 0), Ok(()));
+            assert_eq!(erc20.total_accounts_ever(), 1);
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.total_accounts_ever(), 2);
+
+            // Bob drains to zero and receives again.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer(accounts.alice, 100), Ok(()));
+            assert_eq!(erc20.holder_count(), 1);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.transfer(accounts.bob, 50), Ok(()));
+            assert_eq!(erc20.total_accounts_ever(), 2);
+
+            assert_eq!(erc20.mint(accounts.charlie, 10), Ok(()));
+            assert_eq!(erc20.total_accounts_ever(), 3);
+        }
+
+        #[ink::test]
+        fn to_display_units_works() {
+            let erc20 = Erc20::new(1000);
+            assert_eq!(erc20.to_display_units(0), (0, 0));
+            assert_eq!(erc20.to_display_units(12_345_000_000), (123, 45_000_000));
+            assert_eq!(erc20.to_display_units(500_000_000), (5, 0));
+            assert_eq!(erc20.to_display_units(99_999_999), (0, 99_999_999));
+            assert_eq!(erc20.to_display_units(199_999_999), (1, 99_999_999));
+
+            let erc20 = Erc20::new_with_metadata(1000, "Token".to_string(), "TKN".to_string(), 0)
+                .expect("valid metadata");
+            assert_eq!(erc20.to_display_units(42), (42, 0));
+            let erc20 = Erc20::new_with_metadata(1000, "Token".to_string(), "TKN".to_string(), 255)
+                .expect("valid metadata");
+            assert_eq!(erc20.to_display_units(Balance::MAX), (0, Balance::MAX));
+        }
+
+        #[ink::test]
+        fn mint_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.mint(accounts.bob, 500), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 500);
+            assert_eq!(erc20.total_supply(), 1500);
+
+            assert_eq!(erc20.mint(accounts.bob, Balance::MAX), Err(Error::SupplyOverflow));
+            assert_eq!(erc20.balance_of(accounts.bob), 500);
+            assert_eq!(erc20.total_supply(), 1500);
+        }
+
+        #[ink::test]
+        fn mint_to_zero_address_fails() {
+            let mut erc20 = Erc20::new(1000);
+
+            assert_eq!(
+                erc20.mint(AccountId::from([0u8; 32]), 500),
+                Err(Error::ZeroAddress)
+            );
+            assert_eq!(erc20.total_supply(), 1000);
+        }
+
+        #[ink::test]
+        fn mint_requires_minter_role() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.mint(accounts.bob, 500), Err(Error::MissingRole));
+            assert_eq!(erc20.total_supply(), 1000);
+        }
+
+        #[ink::test]
+        fn grant_and_revoke_minter_role() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert!(erc20.has_role(DEFAULT_ADMIN_ROLE, accounts.alice));
+            assert!(erc20.has_role(MINTER_ROLE, accounts.alice));
+
+            assert_eq!(erc20.grant_role(MINTER_ROLE, accounts.bob), Ok(()));
+            assert!(erc20.has_role(MINTER_ROLE, accounts.bob));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.mint(accounts.bob, 500), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 500);
+            assert_eq!(erc20.grant_role(MINTER_ROLE, accounts.charlie), Err(Error::MissingRole));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.revoke_role(MINTER_ROLE, accounts.bob), Ok(()));
+            assert!(!erc20.has_role(MINTER_ROLE, accounts.bob));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.mint(accounts.bob, 500), Err(Error::MissingRole));
+            assert_eq!(erc20.total_supply(), 1500);
+        }
+
+        #[ink::test]
+        fn transfer_ownership_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.owner(), accounts.alice);
+
+            assert_eq!(erc20.transfer_ownership(accounts.bob), Ok(()));
+            assert_eq!(erc20.owner(), accounts.alice);
+            assert_eq!(erc20.pending_owner(), Some(accounts.bob));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.accept_ownership(), Ok(()));
+            assert_eq!(erc20.owner(), accounts.bob);
+            assert_eq!(erc20.pending_owner(), None);
+            assert_eq!(erc20.pause(), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.transfer_ownership(accounts.charlie), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn ownership_and_freezing_exclude_each_other() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.freeze(accounts.alice), Err(Error::CannotFreezeOwner));
+            assert_eq!(erc20.freeze(accounts.bob), Ok(()));
+            assert_eq!(erc20.transfer_ownership(accounts.bob), Err(Error::AccountFrozen));
+            assert_eq!(erc20.pending_owner(), None);
+
+            // Frozen after being nominated: accepting is rejected.
+            assert_eq!(erc20.transfer_ownership(accounts.charlie), Ok(()));
+            assert_eq!(erc20.freeze(accounts.charlie), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(erc20.accept_ownership(), Err(Error::AccountFrozen));
+            assert_eq!(erc20.owner(), accounts.alice);
+        }
+
+        #[ink::test]
+        fn accept_ownership_rejects_other_accounts() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.accept_ownership(), Err(Error::NotPendingOwner));
+            assert_eq!(erc20.transfer_ownership(accounts.bob), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(erc20.accept_ownership(), Err(Error::NotPendingOwner));
+            assert_eq!(erc20.owner(), accounts.alice);
+            assert_eq!(erc20.pending_owner(), Some(accounts.bob));
+        }
+
+        #[ink::test]
+        fn renounce_ownership_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.renounce_ownership(), Err(Error::NotOwner));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.transfer_ownership(accounts.bob), Ok(()));
+            assert_eq!(erc20.grant_role(DEFAULT_ADMIN_ROLE, accounts.bob), Ok(()));
+            assert!(!erc20.is_renounced());
+            assert_eq!(erc20.renounce_ownership(), Ok(()));
+            assert_eq!(erc20.owner(), AccountId::from([0u8; 32]));
+            assert!(erc20.is_renounced());
+            assert_eq!(erc20.pending_owner(), None);
+
+            assert_eq!(erc20.pause(), Err(Error::NotOwner));
+            assert_eq!(erc20.transfer_ownership(accounts.alice), Err(Error::NotOwner));
+            assert_eq!(erc20.mint(accounts.alice, 1), Err(Error::NotOwner));
+            assert_eq!(erc20.grant_role(MINTER_ROLE, accounts.alice), Err(Error::NotOwner));
+            // Other admins cannot hand out roles either.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.grant_role(MINTER_ROLE, accounts.bob), Err(Error::NotOwner));
+            assert_eq!(erc20.mint(accounts.bob, 1), Err(Error::NotOwner));
+            assert_eq!(erc20.accept_ownership(), Err(Error::NotPendingOwner));
+            assert_eq!(erc20.renounce_ownership(), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn renouncement_keeps_bridge_role() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.grant_role(BRIDGE_ROLE, accounts.charlie), Ok(()));
+            assert_eq!(erc20.renounce_ownership(), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(erc20.bridge_mint(accounts.bob, 100, 1, [1; 32]), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+            assert_eq!(erc20.revoke_role(BRIDGE_ROLE, accounts.charlie), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn pause_blocks_transfers() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.approve(accounts.bob, 500), Ok(()));
+
+            assert_eq!(erc20.pause(), Ok(()));
+            assert!(erc20.paused());
+            assert_eq!(erc20.transfer(accounts.bob, 100), Err(Error::Paused));
+            assert_eq!(erc20.mint(accounts.bob, 100), Err(Error::Paused));
+            assert_eq!(erc20.burn(100), Err(Error::Paused));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.bob, 100),
+                Err(Error::Paused)
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.unpause(), Ok(()));
+            assert!(!erc20.paused());
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+        }
+
+        #[ink::test]
+        fn pause_blocks_new_approvals_but_not_revocations() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.approve(accounts.bob, 500), Ok(()));
+
+            assert_eq!(erc20.pause(), Ok(()));
+            assert_eq!(erc20.approve(accounts.charlie, 100), Err(Error::Paused));
+            assert_eq!(erc20.approve_with_expiry(accounts.charlie, 100, 10_000), Err(Error::Paused));
+            assert_eq!(erc20.increase_allowance(accounts.bob, 1), Err(Error::Paused));
+            assert_eq!(erc20.decrease_allowance(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.approve(accounts.bob, 0), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 0);
+            assert_eq!(erc20.unpause(), Ok(()));
+
+            // Approvals can also be paused on their own.
+            assert_eq!(erc20.set_paused_ops(PAUSE_APPROVE), Ok(()));
+            assert_eq!(erc20.approve(accounts.bob, 100), Err(Error::Paused));
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.set_paused_ops(0), Ok(()));
+            assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
+        }
+
+        #[ink::test]
+        fn paused_ops_gate_individual_operations() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.approve(accounts.bob, 500), Ok(()));
+
+            assert_eq!(erc20.set_paused_ops(PAUSE_MINT), Ok(()));
+            assert_eq!(erc20.paused_ops(), PAUSE_MINT);
+            assert!(!erc20.paused());
+            assert_eq!(erc20.mint(accounts.bob, 100), Err(Error::Paused));
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.burn(100), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.set_paused_ops(0), Err(Error::NotOwner));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.set_paused_ops(PAUSE_TRANSFER_FROM | PAUSE_BURN), Ok(()));
+            assert_eq!(erc20.mint(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.burn(100), Err(Error::Paused));
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.bob, 100),
+                Err(Error::Paused)
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 400);
+        }
+
+        #[ink::test]
+        fn pause_requires_owner() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.pause(), Err(Error::NotOwner));
+            assert!(!erc20.paused());
+        }
+
+        #[ink::test]
+        fn frozen_sender_cannot_transfer() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.transfer(accounts.bob, 500), Ok(()));
+            assert_eq!(erc20.freeze(accounts.bob), Ok(()));
+            assert!(erc20.is_frozen(accounts.bob));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer(accounts.charlie, 100), Err(Error::AccountFrozen));
+            assert_eq!(erc20.approve(accounts.charlie, 100), Err(Error::AccountFrozen));
+            assert_eq!(erc20.balance_of(accounts.bob), 500);
+        }
+
+        #[ink::test]
+        fn frozen_recipient_cannot_receive() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.freeze(accounts.bob), Ok(()));
+
+            assert_eq!(erc20.transfer(accounts.bob, 100), Err(Error::AccountFrozen));
+            assert_eq!(erc20.approve(accounts.bob, 100), Err(Error::AccountFrozen));
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn allowlist_mode_restricts_transfers() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert!(!erc20.allowlist_enabled());
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+
+            assert_eq!(erc20.set_allowlist_enabled(true), Ok(()));
+            assert_eq!(erc20.transfer(accounts.bob, 100), Err(Error::NotAllowed));
+            assert_eq!(erc20.set_allowed(accounts.alice, true), Ok(()));
+            assert_eq!(erc20.transfer(accounts.bob, 100), Err(Error::NotAllowed));
+            assert_eq!(erc20.set_allowed(accounts.bob, true), Ok(()));
+            assert!(erc20.is_allowed(accounts.bob));
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 200);
+
+            // Removing the sender from the list blocks it again.
+            assert_eq!(erc20.set_allowed(accounts.alice, false), Ok(()));
+            assert_eq!(erc20.transfer(accounts.bob, 100), Err(Error::NotAllowed));
+
+            assert_eq!(erc20.set_allowlist_enabled(false), Ok(()));
+            assert_eq!(erc20.transfer(accounts.charlie, 100), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.set_allowlist_enabled(true), Err(Error::NotOwner));
+            assert_eq!(erc20.set_allowed(accounts.bob, false), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn unfreeze_restores_transfers() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.freeze(accounts.bob), Ok(()));
+            assert_eq!(erc20.unfreeze(accounts.bob), Ok(()));
+            assert!(!erc20.is_frozen(accounts.bob));
+
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.freeze(accounts.charlie), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn locked_balance_cannot_move() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.transfer(accounts.bob, 500), Ok(()));
+            assert_eq!(erc20.lock(accounts.bob, 501), Err(Error::InsufficientBalance));
+            assert_eq!(erc20.lock(accounts.bob, 300), Ok(()));
+            assert_eq!(erc20.locked_balance(accounts.bob), 300);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                erc20.transfer(accounts.charlie, 201),
+                Err(Error::InsufficientUnlockedBalance)
+            );
+            assert_eq!(erc20.burn(201), Err(Error::InsufficientUnlockedBalance));
+            assert_eq!(erc20.transfer(accounts.charlie, 200), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 300);
+            // Incoming tokens are freely spendable.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(erc20.transfer(accounts.bob, 50), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer(accounts.charlie, 50), Ok(()));
+            assert_eq!(erc20.unlock(accounts.bob, 100), Err(Error::NotOwner));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.unlock(accounts.bob, 301), Err(Error::InsufficientBalance));
+            assert_eq!(erc20.unlock(accounts.bob, 300), Ok(()));
+            assert_eq!(erc20.locked_balance(accounts.bob), 0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer(accounts.charlie, 300), Ok(()));
+        }
+
+        #[ink::test]
+        fn new_capped_rejects_supply_above_cap() {
+            assert!(matches!(Erc20::new_capped(1001, 1000), Err(Error::CapExceeded)));
+            let erc20 = Erc20::new_capped(1000, 1000).expect("supply within cap");
+            assert_eq!(erc20.cap(), 1000);
+            assert_eq!(Erc20::new(1000).cap(), Balance::MAX);
+        }
+
+        #[ink::test]
+        fn new_with_max_supply_rejects_supply_above_max() {
+            assert!(matches!(
+                Erc20::new_with_max_supply(1001, "Token".to_string(), "TKN".to_string(), 12, 1000),
+                Err(Error::CapExceeded)
+            ));
+            let mut erc20 = Erc20::new_with_max_supply(900, "Token".to_string(), "TKN".to_string(), 12, 1000)
+                .expect("supply within max");
+            assert_eq!(erc20.symbol(), "TKN");
+            assert_eq!(erc20.decimals(), 12);
+            assert_eq!(erc20.max_supply(), Some(1000));
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.mint(accounts.bob, 101), Err(Error::CapExceeded));
+            assert_eq!(erc20.mint(accounts.bob, 100), Ok(()));
+        }
+
+        #[ink::test]
+        fn genesis_info_records_deployment() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(42_000);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.genesis_info(), (1000, accounts.bob, 42_000));
+
+            // Later supply and ownership changes leave it untouched.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(43_000);
+            assert_eq!(erc20.mint(accounts.bob, 500), Ok(()));
+            assert_eq!(erc20.renounce_ownership(), Ok(()));
+            assert_eq!(erc20.genesis_info(), (1000, accounts.bob, 42_000));
+
+            let erc20 = Erc20::new_with_allocations(vec![(accounts.charlie, 100), (accounts.django, 200)])
+                .expect("allocations fit");
+            assert_eq!(erc20.genesis_info(), (300, accounts.bob, 43_000));
+        }
+
+        #[ink::test]
+        fn new_with_allocations_works() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let erc20 = Erc20::new_with_allocations(vec![
+                (accounts.bob, 100),
+                (accounts.charlie, 200),
+                (accounts.django, 300),
+            ])
+            .expect("allocations fit");
+            assert_eq!(erc20.total_supply(), 600);
+            assert_eq!(erc20.balance_of(accounts.alice), 0);
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+            assert_eq!(erc20.balance_of(accounts.charlie), 200);
+            assert_eq!(erc20.balance_of(accounts.django), 300);
+            assert_eq!(erc20.holder_count(), 3);
+            // Only the allocations are minted, without a zero-value mint to
+            // the deployer.
+            #[cfg(not(feature = "no-events"))]
+            assert_eq!(
+                recorded_events()
+                    .into_iter()
+                    .filter_map(|event| match event {
+                        Event::Transfer(Transfer { from: None, to, value, .. }) => Some((to, value)),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>(),
+                vec![(accounts.bob, 100), (accounts.charlie, 200), (accounts.django, 300)]
+            );
+
+            assert!(matches!(
+                Erc20::new_with_allocations(vec![(accounts.eve, Balance::MAX), (accounts.frank, 1)]),
+                Err(Error::SupplyOverflow)
+            ));
+        }
+
+        #[ink::test]
+        fn mint_respects_cap() {
+            let mut erc20 = Erc20::new_capped(1000, 1500).expect("supply within cap");
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.mint(accounts.bob, 500), Ok(()));
+            assert_eq!(erc20.total_supply(), 1500);
+            assert_eq!(erc20.mint(accounts.bob, 1), Err(Error::CapExceeded));
+            assert_eq!(erc20.total_supply(), 1500);
+            assert_eq!(erc20.balance_of(accounts.bob), 500);
+        }
+
+        #[ink::test]
+        fn max_supply_is_fixed_at_construction() {
+            assert!(matches!(Erc20::new_capped(2001, 2000), Err(Error::CapExceeded)));
+            assert_eq!(Erc20::new(1000).max_supply(), None);
+
+            let mut erc20 = Erc20::new_capped(1000, 2000).expect("supply within max");
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.max_supply(), Some(2000));
+            assert_eq!(erc20.mint(accounts.bob, 1000), Ok(()));
+            assert_eq!(erc20.total_supply(), 2000);
+            assert_eq!(erc20.mint(accounts.bob, 1), Err(Error::CapExceeded));
+        }
+
+        #[ink::test]
+        fn snapshot_keeps_historical_balances() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.snapshot(), Ok(1));
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.mint(accounts.bob, 50), Ok(()));
+            assert_eq!(erc20.snapshot(), Ok(2));
+            assert_eq!(erc20.burn(200), Ok(()));
+
+            assert_eq!(erc20.balance_of_at(accounts.alice, 1), Ok(1000));
+            assert_eq!(erc20.balance_of_at(accounts.bob, 1), Ok(0));
+            assert_eq!(erc20.total_supply_at(1), Ok(1000));
+            assert_eq!(erc20.balance_of_at(accounts.alice, 2), Ok(900));
+            assert_eq!(erc20.balance_of_at(accounts.bob, 2), Ok(150));
+            assert_eq!(erc20.total_supply_at(2), Ok(1050));
+            assert_eq!(erc20.balance_of(accounts.alice), 700);
+
+            assert_eq!(erc20.balance_of_at(accounts.alice, 0), Err(Error::InvalidSnapshot));
+            assert_eq!(erc20.balance_of_at(accounts.alice, 3), Err(Error::InvalidSnapshot));
+            assert_eq!(erc20.total_supply_at(3), Err(Error::InvalidSnapshot));
+        }
+
+        #[ink::test]
+        fn snapshot_requires_owner() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.snapshot(), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn transfer_routes_fee_to_treasury() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(10_000);
+            assert_eq!(erc20.set_fee(250, accounts.django), Ok(()));
+
+            assert_eq!(erc20.transfer(accounts.bob, 1000), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 9000);
+            assert_eq!(erc20.balance_of(accounts.bob), 975);
+            assert_eq!(erc20.balance_of(accounts.django), 25);
+            assert_eq!(erc20.total_supply(), 10_000);
+
+            #[cfg(not(feature = "no-events"))]
+            let events = recorded_events();
+            #[cfg(not(feature = "no-events"))]
+            assert!(matches!(
+                &events[events.len() - 2],
+                Event::Transfer(Transfer { to, value: 975, .. }) if *to == accounts.bob
+            ));
+            #[cfg(not(feature = "no-events"))]
+            assert!(matches!(
+                events.last(),
+                Some(Event::Transfer(Transfer { to, value: 25, .. })) if *to == accounts.django
+            ));
+        }
+
+        #[ink::test]
+        fn fee_exempt_accounts_pay_no_fee() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(10_000);
+            assert_eq!(erc20.set_fee(250, accounts.django), Ok(()));
+            assert_eq!(erc20.set_fee_exempt(accounts.alice, true), Ok(()));
+            assert!(erc20.is_fee_exempt(accounts.alice));
+
+            assert_eq!(erc20.transfer(accounts.bob, 2000), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 2000);
+            assert_eq!(erc20.balance_of(accounts.django), 0);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer(accounts.charlie, 1000), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.charlie), 975);
+            assert_eq!(erc20.balance_of(accounts.django), 25);
+
+            // Transfers into an exempt account are free as well.
+            assert_eq!(erc20.transfer(accounts.alice, 1000), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 9000);
+
+            assert_eq!(erc20.set_fee_exempt(accounts.bob, true), Err(Error::NotOwner));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.set_fee_exempt(accounts.alice, false), Ok(()));
+            assert!(!erc20.is_fee_exempt(accounts.alice));
+        }
+
+        #[ink::test]
+        fn check_invariant_detects_desynced_aggregates() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.set_excluded(accounts.bob, true), Ok(()));
+            assert!(erc20.check_invariant());
+
+            erc20.excluded_balance_total = 1001;
+            assert!(!erc20.check_invariant());
+            erc20.excluded_balance_total = 100;
+            assert!(erc20.check_invariant());
+
+            erc20.holder_count = 0;
+            assert!(!erc20.check_invariant());
+            erc20.holder_count = 2;
+            assert!(erc20.check_invariant());
+
+            erc20.escrowed = 901;
+            assert!(!erc20.check_invariant());
+            erc20.escrowed = 0;
+            assert!(erc20.check_invariant());
+        }
+
+        #[ink::test]
+        fn circulating_supply_excludes_treasury() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(10_000);
+            assert_eq!(erc20.set_fee(250, accounts.django), Ok(()));
+            assert_eq!(erc20.transfer(accounts.django, 2000), Ok(()));
+            assert_eq!(erc20.circulating_supply(), 10_000);
+
+            assert_eq!(erc20.set_excluded(accounts.django, true), Ok(()));
+            assert!(erc20.is_excluded(accounts.django));
+            assert_eq!(erc20.circulating_supply(), 8000);
+
+            // Fees flowing into the treasury leave circulation.
+            assert_eq!(erc20.transfer(accounts.bob, 1000), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.django), 2025);
+            assert_eq!(erc20.circulating_supply(), 7975);
+
+            assert_eq!(erc20.set_excluded(accounts.django, false), Ok(()));
+            assert_eq!(erc20.circulating_supply(), 10_000);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.set_excluded(accounts.bob, true), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn transfer_quota_tracks_window() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.transfer_quota(accounts.alice), (Balance::MAX, 0));
+
+            assert_eq!(erc20.set_transfer_limit(100, 60), Ok(()));
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(erc20.transfer_quota(accounts.alice), (100, 0));
+            assert_eq!(erc20.transfer(accounts.bob, 30), Ok(()));
+            assert_eq!(erc20.transfer_quota(accounts.alice), (70, 60));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(21_500);
+            assert_eq!(erc20.transfer(accounts.bob, 70), Ok(()));
+            assert_eq!(erc20.transfer_quota(accounts.alice), (0, 40));
+            assert_eq!(erc20.transfer(accounts.bob, 1), Err(Error::TransferLimitExceeded));
+
+            // The window has rolled over.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(61_000);
+            assert_eq!(erc20.transfer_quota(accounts.alice), (100, 0));
+        }
+
+        #[ink::test]
+        fn transfer_limit_resets_after_window() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.set_transfer_limit(100, 60), Ok(()));
+            assert_eq!(erc20.transfer_limit(), (100, 60));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(erc20.transfer(accounts.bob, 60), Ok(()));
+            assert_eq!(erc20.approve(accounts.charlie, 100), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.bob, 40), Ok(()));
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.bob, 1),
+                Err(Error::TransferLimitExceeded)
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.transfer(accounts.bob, 1), Err(Error::TransferLimitExceeded));
+            assert_eq!(erc20.balance_of(accounts.alice), 900);
+
+            // Other accounts have their own quota.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer(accounts.charlie, 100), Ok(()));
+
+            // The window is 60 seconds of millisecond timestamps.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(60_999);
+            assert_eq!(erc20.transfer(accounts.bob, 1), Err(Error::TransferLimitExceeded));
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(61_000);
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 800);
+
+            assert_eq!(erc20.set_transfer_limit(0, 0), Ok(()));
+            assert_eq!(erc20.transfer(accounts.bob, 500), Ok(()));
+        }
+
+        #[ink::test]
+        fn min_balance_rejects_dust() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.set_min_balance(100), Ok(()));
+            assert_eq!(erc20.min_balance(), 100);
+
+            assert_eq!(erc20.transfer(accounts.bob, 950), Err(Error::DustBalance));
+            assert_eq!(erc20.transfer(accounts.bob, 900), Ok(()));
+
+            // Emptying the account entirely is allowed.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer(accounts.charlie, 850), Err(Error::DustBalance));
+            assert_eq!(erc20.transfer(accounts.charlie, 900), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.set_min_balance(0), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn flash_fee_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.flash_fee(1000), 0);
+            assert_eq!(erc20.set_flash_fee(9), Ok(()));
+            assert_eq!(erc20.flash_fee(10_000), 9);
+            assert_eq!(erc20.flash_fee(1000), 0);
+            assert_eq!(erc20.set_flash_fee(10_001), Err(Error::InvalidFee));
+            assert_eq!(erc20.flash_loan(accounts.bob, 0, vec![]), Err(Error::ZeroAmount));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.set_flash_fee(0), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn transfer_cooldown_works() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.approve(accounts.bob, 500), Ok(()));
+            assert_eq!(erc20.set_transfer_cooldown(30), Ok(()));
+            assert_eq!(erc20.transfer_cooldown_secs(), 30);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(30_999);
+            assert_eq!(erc20.transfer(accounts.bob, 100), Err(Error::Cooldown));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.charlie, 100),
+                Err(Error::Cooldown)
+            );
+            // The cooldown applies per sender.
+            assert_eq!(erc20.transfer(accounts.charlie, 50), Ok(()));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(31_000);
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.charlie, 100),
+                Ok(())
+            );
+            assert_eq!(erc20.balance_of(accounts.alice), 800);
+            assert_eq!(erc20.set_transfer_cooldown(0), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn amount_received_and_to_send_round_trip() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.amount_received(1000), Ok(1000));
+            assert_eq!(erc20.amount_to_send(1000), 1000);
+
+            assert_eq!(erc20.set_fee(300, accounts.django), Ok(()));
+            assert_eq!(erc20.amount_received(1000), Ok(970));
+            assert_eq!(erc20.amount_to_send(970), 999);
+            assert_eq!(erc20.amount_to_send(97), 99);
+            assert_eq!(erc20.amount_received(98), Ok(96));
+            assert_eq!(erc20.amount_to_send(0), 0);
+
+            for desired in 1..=500 {
+                let gross = erc20.amount_to_send(desired);
+                assert!(erc20.amount_received(gross).expect("fee within value") >= desired);
+                assert!(erc20.amount_received(gross - 1).expect("fee within value") < desired);
+            }
+
+            assert_eq!(erc20.set_fee(10_000, accounts.django), Ok(()));
+            assert_eq!(erc20.amount_to_send(1), Balance::MAX);
+        }
+
+        #[ink::test]
+        fn rounding_mode_applies_to_fee() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(10_000);
+            assert_eq!(erc20.rounding(), RoundingMode::Down);
+            assert_eq!(erc20.set_fee(250, accounts.django), Ok(()));
+
+            // Fees of 25.25 and 25.5 tokens.
+            assert_eq!(erc20.amount_received(1010), Ok(985));
+            assert_eq!(erc20.amount_received(1020), Ok(995));
+
+            assert_eq!(erc20.set_rounding(RoundingMode::Up), Ok(()));
+            assert_eq!(erc20.rounding(), RoundingMode::Up);
+            assert_eq!(erc20.amount_received(1010), Ok(984));
+            assert_eq!(erc20.amount_received(1020), Ok(994));
+
+            assert_eq!(erc20.set_rounding(RoundingMode::Nearest), Ok(()));
+            assert_eq!(erc20.amount_received(1010), Ok(985));
+            assert_eq!(erc20.amount_received(1020), Ok(994));
+            assert_eq!(erc20.transfer(accounts.bob, 1020), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 994);
+            assert_eq!(erc20.balance_of(accounts.django), 26);
+
+            for rounding in [RoundingMode::Down, RoundingMode::Up, RoundingMode::Nearest] {
+                assert_eq!(erc20.set_rounding(rounding), Ok(()));
+                for desired in 1..=500 {
+                    let gross = erc20.amount_to_send(desired);
+                    assert!(erc20.amount_received(gross).expect("fee within value") >= desired);
+                    assert!(erc20.amount_received(gross - 1).expect("fee within value") < desired);
+                }
+            }
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.set_rounding(RoundingMode::Down), Err(Error::NotOwner));
+        }
+
+        #[cfg(not(feature = "no-events"))]
+        #[ink::test]
+        fn low_balance_warning_fires_on_crossing() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.set_low_balance_threshold(500), Ok(()));
+            let warnings = || {
+                recorded_events()
+                    .into_iter()
+                    .filter(|event| matches!(event, Event::LowBalanceWarning(_)))
+                    .count()
+            };
+
+            // Still at the threshold.
+            assert_eq!(erc20.transfer(accounts.bob, 500), Ok(()));
+            assert_eq!(warnings(), 0);
+
+            assert_eq!(erc20.transfer(accounts.bob, 1), Ok(()));
+            assert_eq!(warnings(), 1);
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::LowBalanceWarning(LowBalanceWarning { account, balance: 499 }))
+                    if *account == accounts.alice
+            ));
+
+            // Already below, so no new crossing.
+            assert_eq!(erc20.transfer(accounts.bob, 1), Ok(()));
+            assert_eq!(warnings(), 1);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.set_low_balance_threshold(0), Err(Error::NotOwner));
+        }
+
+        #[cfg(feature = "no-events")]
+        #[ink::test]
+        fn no_events_feature_skips_emission() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.approve(accounts.bob, 50), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 900);
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+            assert!(recorded_events().is_empty());
+        }
+
+        #[ink::test]
+        fn set_fee_rejects_invalid_fee() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.set_fee(10_001, accounts.django), Err(Error::InvalidFee));
+            assert_eq!(erc20.fee_bps(), 0);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.set_fee(100, accounts.django), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn reentrant_calls_are_rejected() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.approve(accounts.alice, 1000), Ok(()));
+
+            // Simulate a call arriving while another guarded message is executing.
+            erc20.locked = true;
+            assert_eq!(erc20.transfer(accounts.bob, 100), Err(Error::Reentrant));
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.bob, 100),
+                Err(Error::Reentrant)
+            );
+            assert_eq!(erc20.mint(accounts.bob, 100), Err(Error::Reentrant));
+            assert_eq!(erc20.burn(100), Err(Error::Reentrant));
+
+            erc20.locked = false;
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert!(!erc20.locked);
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+        }
+
+        #[ink::test]
+        fn callbacks_only_let_tokens_move() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Simulate a message reentering while a callback is in flight.
+            erc20.in_callback = true;
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.flash_loan(accounts.bob, 100, vec![]), Err(Error::Reentrant));
+            assert_eq!(erc20.mint(accounts.bob, 100), Err(Error::Reentrant));
+            assert_eq!(erc20.burn(100), Err(Error::Reentrant));
+            assert_eq!(erc20.pause(), Err(Error::Reentrant));
+            assert_eq!(erc20.total_supply(), 1000);
+
+            erc20.in_callback = false;
+            assert_eq!(erc20.mint(accounts.bob, 100), Ok(()));
+        }
+
+        #[ink::test]
+        fn with_callback_keeps_changes_from_both_sides() {
+            let mut erc20 = Erc20::new(1000);
+            let key = <Erc20 as ink::storage::traits::StorageKey>::KEY;
+
+            // Changed earlier in the outer message, before the callback.
+            erc20.min_balance = 7;
+            let result = erc20.with_callback(|_| {
+                // A reentrant message loads the root, sees the outer change
+                // and the callback flag, and stores its own change.
+                let mut root = ink::env::get_contract_storage::<_, Erc20>(&key)
+                    .expect("root decodes")
+                    .expect("root was written");
+                assert_eq!(root.min_balance, 7);
+                assert!(root.in_callback);
+                root.total_supply = 1500;
+                ink::env::set_contract_storage(&key, &root);
+                42
+            });
+
+            assert_eq!(result, Ok(42));
+            assert_eq!(erc20.min_balance, 7);
+            assert_eq!(erc20.total_supply(), 1500);
+            assert!(!erc20.in_callback);
+
+            // Callbacks do not nest.
+            erc20.in_callback = true;
+            assert_eq!(erc20.with_callback(|_| 42), Err(Error::Reentrant));
+            assert_eq!(
+                erc20.approve_and_call(ink::env::test::default_accounts::<ink::env::DefaultEnvironment>().bob, 1, vec![]),
+                Err(Error::Reentrant)
+            );
+        }
+
+        #[ink::test]
+        fn deposit_and_withdraw_work() {
+            let mut erc20 = Erc20::new(0);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(500);
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contract, 500);
+            assert_eq!(erc20.deposit(), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 500);
+            assert_eq!(erc20.total_supply(), 500);
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            let bob_native = ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob)
+                .expect("bob has a native balance");
+            assert_eq!(erc20.withdraw(200), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 300);
+            assert_eq!(erc20.total_supply(), 300);
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob),
+                Ok(bob_native + 200)
+            );
+            assert_eq!(erc20.withdraw(301), Err(Error::InsufficientBalance));
+            assert_eq!(erc20.withdraw(0), Err(Error::ZeroAmount));
+            assert_eq!(erc20.deposit(), Err(Error::ZeroAmount));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.freeze(accounts.bob), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.withdraw(100), Err(Error::AccountFrozen));
+            assert_eq!(erc20.balance_of(accounts.bob), 300);
+        }
+
+        #[ink::test]
+        fn sweep_native_keeps_deposits() {
+            let mut erc20 = Erc20::new(0);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(500);
+            assert_eq!(erc20.deposit(), Ok(()));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(erc20.sweep_native(accounts.bob), Err(Error::NotOwner));
+
+            // 200 on top of the deposit, e.g. from a refund.
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(contract, 700);
+            let charlie_native =
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.charlie)
+                    .expect("charlie has a native balance");
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.sweep_native(accounts.charlie), Ok(()));
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.charlie),
+                Ok(charlie_native + 200)
+            );
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(contract),
+                Ok(500)
+            );
+
+            // Nothing left to sweep.
+            assert_eq!(erc20.sweep_native(accounts.charlie), Ok(()));
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(contract),
+                Ok(500)
+            );
+        }
+
+        #[ink::test]
+        fn distribution_is_claimed_pro_rata() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.transfer(accounts.bob, 600), Ok(()));
+            assert_eq!(erc20.transfer(accounts.charlie, 300), Ok(()));
+            let id = erc20.snapshot().expect("owner can snapshot");
+            assert_eq!(erc20.distribute(id + 1, 100), Err(Error::InvalidSnapshot));
+            assert_eq!(erc20.distribute(id, 100), Ok(()));
+            assert_eq!(erc20.distribute(id, 100), Err(Error::DistributionExists));
+            assert_eq!(erc20.balance_of(accounts.frank), 100);
+
+            // Balances moved after the snapshot do not change the shares.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(erc20.transfer(accounts.bob, 300), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.claimable(id, accounts.bob), Ok(60));
+            assert_eq!(erc20.claim(id), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 960);
+            assert_eq!(erc20.claim(id), Err(Error::AlreadyClaimed));
+            assert_eq!(erc20.claimable(id, accounts.bob), Ok(0));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(erc20.claim(id), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.charlie), 30);
+
+            // Alice's share stays escrowed until she claims it.
+            assert_eq!(erc20.balance_of(accounts.frank), 10);
+            assert_eq!(erc20.claim(id + 1), Err(Error::NoDistribution));
+        }
+
+        #[ink::test]
+        fn pause_blocks_distributions() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.transfer(accounts.bob, 500), Ok(()));
+            let id = erc20.snapshot().expect("owner can snapshot");
+
+            assert_eq!(erc20.pause(), Ok(()));
+            assert_eq!(erc20.distribute(id, 100), Err(Error::Paused));
+            assert_eq!(erc20.unpause(), Ok(()));
+            assert_eq!(erc20.distribute(id, 100), Ok(()));
+
+            assert_eq!(erc20.set_paused_ops(PAUSE_TRANSFER), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.claim(id), Err(Error::Paused));
+            assert_eq!(erc20.balance_of(accounts.bob), 500);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.set_paused_ops(0), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.claim(id), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 550);
+        }
+
+        #[ink::test]
+        fn burn_treasury_keeps_escrow() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.transfer(accounts.frank, 300), Ok(()));
+            assert_eq!(erc20.create_vesting(accounts.bob, 100, 0, 1000), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.frank), 400);
+
+            assert_eq!(erc20.burn_treasury(301), Err(Error::InsufficientBalance));
+            assert_eq!(erc20.burn_treasury(200), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.frank), 200);
+            assert_eq!(erc20.total_supply(), 800);
+            #[cfg(not(feature = "no-events"))]
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::Burn(Burn { from, value: 200 })) if *from == accounts.frank
+            ));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.burn_treasury(100), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn airdrop_selection_is_deterministic() {
+            let erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(
+                erc20.is_airdrop_winner(accounts.bob, 42, 5000),
+                erc20.is_airdrop_winner(accounts.bob, 42, 5000)
+            );
+            assert!(!erc20.is_airdrop_winner(accounts.bob, 42, 0));
+            assert!(erc20.is_airdrop_winner(accounts.bob, 42, 10_000));
+
+            let winners = (0..2000u32)
+                .filter(|i| {
+                    let mut account = [0u8; 32];
+                    account[..4].copy_from_slice(&i.to_le_bytes());
+                    erc20.is_airdrop_winner(AccountId::from(account), 7, 2500)
+                })
+                .count();
+            assert!((400..600).contains(&winners), "{winners} winners");
+        }
+
+        #[ink::test]
+        fn distribute_from_treasury_works() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.transfer(accounts.frank, 600), Ok(()));
+            let payouts = vec![(accounts.bob, 100), (accounts.charlie, 200), (accounts.django, 300)];
+
+            assert_eq!(
+                erc20.distribute_from_treasury(payouts.clone()),
+                Err(Error::MissingRole)
+            );
+            assert_eq!(erc20.grant_role(TREASURER_ROLE, accounts.alice), Ok(()));
+
+            // Underfunded: nothing moves.
+            let mut too_much = payouts.clone();
+            too_much.push((accounts.eve, 1));
+            assert_eq!(
+                erc20.distribute_from_treasury(too_much),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(erc20.balance_of(accounts.frank), 600);
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+
+            assert_eq!(erc20.distribute_from_treasury(payouts), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.frank), 0);
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+            assert_eq!(erc20.balance_of(accounts.charlie), 200);
+            assert_eq!(erc20.balance_of(accounts.django), 300);
+            #[cfg(not(feature = "no-events"))]
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::Transfer(Transfer { from: Some(from), to, value: 300, .. }))
+                    if *from == accounts.frank && *to == accounts.django
+            ));
+        }
+
+        #[ink::test]
+        fn pause_blocks_vesting() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+
+            assert_eq!(erc20.pause(), Ok(()));
+            assert_eq!(erc20.create_vesting(accounts.bob, 400, 0, 1000), Err(Error::Paused));
+            assert_eq!(erc20.unpause(), Ok(()));
+            assert_eq!(erc20.create_vesting(accounts.bob, 400, 0, 1000), Ok(()));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(erc20.set_paused_ops(PAUSE_TRANSFER), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.release(), Err(Error::Paused));
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.set_paused_ops(0), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.release(), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 400);
+        }
+
+        #[ink::test]
+        fn vesting_releases_linearly() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            let contract = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(500);
+            assert_eq!(erc20.create_vesting(accounts.bob, 400, 1000, 1000), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 600);
+            assert_eq!(erc20.balance_of(contract), 400);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            // 0% of the window.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(erc20.vested_amount(accounts.bob), 0);
+            assert_eq!(erc20.release(), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+
+            // 50% of the window.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1500);
+            assert_eq!(erc20.vested_amount(accounts.bob), 200);
+            assert_eq!(erc20.release(), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 200);
+
+            // 100% of the window, only the remainder is released.
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2500);
+            assert_eq!(erc20.vested_amount(accounts.bob), 400);
+            assert_eq!(erc20.release(), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 400);
+            assert_eq!(erc20.balance_of(contract), 0);
+        }
+
+        #[ink::test]
+        fn vesting_rejects_invalid_calls() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.create_vesting(accounts.bob, 100, 0, 10), Ok(()));
+            assert_eq!(erc20.create_vesting(accounts.bob, 100, 0, 10), Err(Error::VestingExists));
+            assert_eq!(erc20.create_vesting(accounts.charlie, 1000, 0, 10), Err(Error::InsufficientBalance));
+            assert_eq!(erc20.release(), Err(Error::NoVesting));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.create_vesting(accounts.charlie, 100, 0, 10), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn self_delegation_counts_balance_as_votes() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.get_votes(accounts.alice), 0);
+
+            assert_eq!(erc20.delegate(accounts.alice), Ok(()));
+            assert_eq!(erc20.delegates(accounts.alice), Some(accounts.alice));
+            assert_eq!(erc20.get_votes(accounts.alice), 1000);
+            assert_eq!(erc20.mint(accounts.alice, 500), Ok(()));
+            assert_eq!(erc20.get_votes(accounts.alice), 1500);
+            assert_eq!(erc20.burn(200), Ok(()));
+            assert_eq!(erc20.get_votes(accounts.alice), 1300);
+        }
+
+        #[ink::test]
+        fn redelegation_moves_votes() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.delegate(accounts.bob), Ok(()));
+            assert_eq!(erc20.get_votes(accounts.bob), 1000);
+            assert_eq!(erc20.delegate(accounts.charlie), Ok(()));
+            assert_eq!(erc20.get_votes(accounts.bob), 0);
+            assert_eq!(erc20.get_votes(accounts.charlie), 1000);
+        }
+
+        #[ink::test]
+        fn transfers_move_votes_between_delegates() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(10);
+            assert_eq!(erc20.delegate(accounts.alice), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.delegate(accounts.charlie), Ok(()));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(20);
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.transfer(accounts.bob, 300), Ok(()));
+            assert_eq!(erc20.get_votes(accounts.alice), 700);
+            assert_eq!(erc20.get_votes(accounts.charlie), 300);
+
+            assert_eq!(erc20.get_past_votes(accounts.alice, 5), 0);
+            assert_eq!(erc20.get_past_votes(accounts.alice, 15), 1000);
+            assert_eq!(erc20.get_past_votes(accounts.alice, 20), 700);
+            assert_eq!(erc20.get_past_votes(accounts.charlie, 15), 0);
+        }
+
+        #[ink::test]
+        fn vote_checkpoints_are_bounded() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.delegate(accounts.alice), Ok(()));
+
+            for timestamp in 1..=(MAX_VOTE_CHECKPOINTS as u64 + 5) {
+                ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(timestamp);
+                assert_eq!(erc20.burn(1), Ok(()));
+            }
+            let checkpoints = erc20.vote_checkpoints.get(accounts.alice).unwrap_or_default();
+            assert_eq!(checkpoints.len(), MAX_VOTE_CHECKPOINTS);
+            assert_eq!(erc20.get_votes(accounts.alice), 1000 - MAX_VOTE_CHECKPOINTS as Balance - 5);
+        }
+
+        #[ink::test]
+        fn reclaim_token_requires_owner() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                erc20.reclaim_token(accounts.charlie, accounts.bob),
+                Err(Error::NotOwner)
+            );
+        }
+
+        #[ink::test]
+        fn burn_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.burn(300), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 700);
+            assert_eq!(erc20.total_supply(), 700);
+
+            assert_eq!(erc20.burn(701), Err(Error::InsufficientBalance));
+            assert_eq!(erc20.balance_of(accounts.alice), 700);
+            assert_eq!(erc20.total_supply(), 700);
+        }
+
+        #[ink::test]
+        fn restricted_accounts_cannot_burn() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.transfer(accounts.bob, 300), Ok(()));
+            assert_eq!(erc20.freeze(accounts.bob), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.burn(100), Err(Error::AccountFrozen));
+            assert_eq!(erc20.bridge_burn(100, 7, vec![0xab; 20]), Err(Error::AccountFrozen));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.unfreeze(accounts.bob), Ok(()));
+            assert_eq!(erc20.set_allowlist_enabled(true), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.bridge_burn(100, 7, vec![0xab; 20]), Err(Error::NotAllowed));
+            assert_eq!(erc20.balance_of(accounts.bob), 300);
+            assert_eq!(erc20.total_supply(), 1000);
+        }
+
+        #[ink::test]
+        fn zero_mint_and_burn_are_rejected() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let events_before = recorded_events().len();
+
+            assert_eq!(erc20.burn(0), Err(Error::ZeroAmount));
+            assert_eq!(erc20.burn_from(accounts.alice, 0), Err(Error::ZeroAmount));
+            assert_eq!(erc20.mint(accounts.bob, 0), Err(Error::ZeroAmount));
+            assert_eq!(erc20.total_supply(), 1000);
+            assert_eq!(recorded_events().len(), events_before);
+        }
+
+        #[ink::test]
+        fn bridge_burn_and_mint_work() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.bridge_burn(300, 7, vec![0xab; 20]), Ok(()));
+            assert_eq!(erc20.total_supply(), 700);
+            #[cfg(not(feature = "no-events"))]
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::BridgeBurn(BridgeBurn { value: 300, dest_chain: 7, dest_recipient, .. }))
+                    if dest_recipient == &vec![0xab; 20]
+            ));
+            assert_eq!(
+                erc20.bridge_burn(1, 7, vec![0; MAX_BRIDGE_RECIPIENT_LEN + 1]),
+                Err(Error::DataTooLarge)
+            );
+
+            let src_tx = [9; 32];
+            assert_eq!(erc20.bridge_mint(accounts.bob, 300, 7, src_tx), Err(Error::MissingRole));
+            assert_eq!(erc20.grant_role(BRIDGE_ROLE, accounts.charlie), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(erc20.bridge_mint(accounts.bob, 300, 7, src_tx), Ok(()));
+            assert!(erc20.is_bridge_tx_processed(src_tx));
+            assert_eq!(erc20.balance_of(accounts.bob), 300);
+
+            // Replaying the source transaction is rejected.
+            assert_eq!(
+                erc20.bridge_mint(accounts.bob, 300, 7, src_tx),
+                Err(Error::AlreadyProcessed)
+            );
+            assert_eq!(erc20.total_supply(), 1000);
+        }
+
+        #[ink::test]
+        fn burn_from_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.approve(accounts.bob, 500), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.burn_from(accounts.alice, 200), Err(Error::MissingRole));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.grant_role(MINTER_ROLE, accounts.bob), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.burn_from(accounts.alice, 600), Err(Error::InsufficientAllowance));
+            assert_eq!(erc20.burn_from(accounts.alice, 200), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 800);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 300);
+            assert_eq!(erc20.total_supply(), 800);
+        }
+
+        #[ink::test]
+        fn total_burned_accumulates() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.total_burned(), 0);
+
+            assert_eq!(erc20.burn(100), Ok(()));
+            assert_eq!(erc20.total_burned(), 100);
+            assert_eq!(erc20.approve(accounts.alice, 50), Ok(()));
+            assert_eq!(erc20.burn_from(accounts.alice, 50), Ok(()));
+            assert_eq!(erc20.total_burned(), 150);
+
+            // Transfers and failed burns leave the counter alone.
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.burn(10_000), Err(Error::InsufficientBalance));
+            assert_eq!(erc20.total_burned(), 150);
+        }
+
+        #[ink::test]
+        fn burn_from_checks_balance_after_allowance() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.approve(accounts.bob, 1000), Ok(()));
+            assert_eq!(erc20.transfer(accounts.charlie, 900), Ok(()));
+            assert_eq!(erc20.grant_role(MINTER_ROLE, accounts.bob), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.burn_from(accounts.alice, 200), Err(Error::InsufficientBalance));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 1000);
+            assert_eq!(erc20.total_supply(), 1000);
+        }
+
+        #[ink::test]
+        fn approve_does_not_require_balance() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.approve(accounts.charlie, 5000), Ok(()));
+            assert_eq!(erc20.allowance(accounts.bob, accounts.charlie), 5000);
+        }
+
+        #[cfg(not(feature = "no-events"))]
+        #[ink::test]
+        fn transfer_from_emits_approval() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.approve(accounts.bob, 500), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.charlie, 200), Ok(()));
+
+            let events = recorded_events();
+            assert!(matches!(
+                events.last(),
+                Some(Event::Transfer(Transfer { value: 200, spender, .. }))
+                    if *spender == Some(accounts.bob)
+            ));
+            assert!(matches!(
+                &events[events.len() - 2],
+                Event::TransferMeta(TransferMeta { value: 200, .. })
+            ));
+            assert!(matches!(
+                &events[events.len() - 3],
+                Event::Approval(Approval { from, to, value: 300 })
+                    if *from == accounts.alice && *to == accounts.bob
+            ));
+        }
+
+        #[cfg(not(feature = "no-events"))]
+        #[ink::test]
+        fn transfer_event_records_spender() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::Transfer(Transfer { value: 100, spender: None, .. }))
+            ));
+
+            assert_eq!(erc20.approve(accounts.django, 100), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.bob, 50), Ok(()));
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::Transfer(Transfer { from, value: 50, spender: Some(spender), .. }))
+                    if *from == Some(accounts.alice) && *spender == accounts.django
+            ));
+        }
+
+        #[ink::test]
+        fn infinite_allowance_is_not_decremented() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.approve(accounts.bob, u128::MAX), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.charlie, 100), Ok(()));
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.charlie, 200), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), u128::MAX);
+            assert_eq!(erc20.balance_of(accounts.charlie), 300);
+        }
+
+        #[ink::test]
+        fn oversized_batches_are_rejected() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.max_batch(), 64);
+            assert_eq!(erc20.grant_role(TREASURER_ROLE, accounts.alice), Ok(()));
+            let events_before = recorded_events().len();
+
+            let recipients = vec![(accounts.bob, 1); MAX_BATCH + 1];
+            assert_eq!(
+                erc20.transfer_batch(recipients.clone()),
+                Err(Error::BatchTooLarge)
+            );
+            assert_eq!(
+                erc20.distribute_from_treasury(recipients.clone()),
+                Err(Error::BatchTooLarge)
+            );
+            assert!(matches!(
+                Erc20::new_with_allocations(recipients),
+                Err(Error::BatchTooLarge)
+            ));
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(recorded_events().len(), events_before);
+
+            assert_eq!(erc20.transfer_batch(vec![(accounts.bob, 1); MAX_BATCH]), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 64);
+        }
+
+        #[ink::test]
+        fn migrate_from_checks_before_calling_old_token() {
+            let mut erc20 = Erc20::new(0);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.migration_source(), None);
+            assert_eq!(erc20.set_migration_source(Some(accounts.django)), Ok(()));
+            assert_eq!(erc20.migration_source(), Some(accounts.django));
+            erc20.migrated.insert(accounts.bob, &true);
+            assert!(erc20.is_migrated(accounts.bob));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                erc20.migrate_from(accounts.django, accounts.bob),
+                Err(Error::AlreadyMigrated)
+            );
+            // Only from the configured token, and only for the caller.
+            assert_eq!(
+                erc20.migrate_from(accounts.eve, accounts.bob),
+                Err(Error::MigrationNotAllowed)
+            );
+            assert_eq!(
+                erc20.migrate_from(accounts.django, accounts.charlie),
+                Err(Error::MigrationNotAllowed)
+            );
+            assert_eq!(erc20.set_migration_source(None), Err(Error::MissingRole));
+        }
+
+        #[ink::test]
+        fn transfer_batch_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(
+                erc20.transfer_batch(vec![(accounts.bob, 100), (accounts.charlie, 200)]),
+                Ok(())
+            );
+            assert_eq!(erc20.balance_of(accounts.alice), 700);
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+            assert_eq!(erc20.balance_of(accounts.charlie), 200);
+        }
+
+        #[ink::test]
+        fn transfer_batch_fails_without_changes() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(
+                erc20.transfer_batch(vec![(accounts.bob, 600), (accounts.charlie, 500)]),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(erc20.balance_of(accounts.alice), 1000);
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert_eq!(erc20.balance_of(accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn transfer_with_data_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.transfer_with_data(accounts.bob, 100, vec![1, 2, 3]), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+            #[cfg(not(feature = "no-events"))]
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::TransferData(TransferData { value: 100, data, .. })) if data == &vec![1, 2, 3]
+            ));
+
+            assert_eq!(
+                erc20.transfer_with_data(accounts.bob, 100, vec![0; MAX_TRANSFER_DATA_LEN + 1]),
+                Err(Error::DataTooLarge)
+            );
+            assert_eq!(
+                erc20.transfer_with_data(accounts.bob, 1000, vec![1]),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+        }
+
+        fn permit_signer(seed: u8) -> (secp256k1::SecretKey, AccountId) {
+            let secp = secp256k1::Secp256k1::new();
+            let secret_key = secp256k1::SecretKey::from_slice(&[seed; 32]).expect("valid secret key");
+            let public_key = secp256k1::PublicKey::from_secret_key(&secp, &secret_key).serialize();
+            let mut account = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&public_key, &mut account);
+            (secret_key, AccountId::from(account))
+        }
+
+        fn sign_permit(secret_key: &secp256k1::SecretKey, message_hash: [u8; 32]) -> [u8; 65] {
+            let secp = secp256k1::Secp256k1::new();
+            let message = secp256k1::Message::from_slice(&message_hash).expect("valid message hash");
+            let (recovery_id, compact) = secp
+                .sign_ecdsa_recoverable(&message, secret_key)
+                .serialize_compact();
+            let mut signature = [0u8; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = recovery_id.to_i32() as u8;
+            signature
+        }
+
+        #[ink::test]
+        fn bump_nonce_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.nonce_of(accounts.alice), 0);
+
+            assert_eq!(erc20.bump_nonce(), Ok(()));
+            assert_eq!(erc20.bump_nonce(), Ok(()));
+            assert_eq!(erc20.nonce_of(accounts.alice), 2);
+            assert_eq!(erc20.nonces(accounts.alice), 2);
+            assert_eq!(erc20.nonce_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn counters_report_overflow() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            erc20.nonces.insert(accounts.alice, &u64::MAX);
+            assert_eq!(erc20.bump_nonce(), Err(Error::ArithmeticOverflow));
+
+            erc20.current_snapshot_id = u32::MAX;
+            assert_eq!(erc20.snapshot(), Err(Error::ArithmeticOverflow));
+            erc20.current_snapshot_id = 0;
+
+            erc20.transfer_seq = u64::MAX;
+            assert_eq!(erc20.transfer(accounts.bob, 100), Err(Error::ArithmeticOverflow));
+            erc20.transfer_seq = 1;
+
+            erc20.holder_count = u32::MAX;
+            assert_eq!(erc20.transfer(accounts.charlie, 100), Err(Error::ArithmeticOverflow));
+        }
+
+        #[ink::test]
+        fn bump_nonce_invalidates_permit() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let (secret_key, owner) = permit_signer(7);
+
+            let signature = sign_permit(&secret_key, erc20.permit_hash(owner, accounts.bob, 300, 0, 100));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(owner);
+            assert_eq!(erc20.bump_nonce(), Ok(()));
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 300, 100, signature),
+                Err(Error::InvalidSignature)
+            );
+        }
+
+        #[ink::test]
+        fn permit_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let (secret_key, owner) = permit_signer(7);
+            assert_eq!(erc20.transfer(owner, 500), Ok(()));
+
+            let signature = sign_permit(&secret_key, erc20.permit_hash(owner, accounts.bob, 300, 0, 100));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(erc20.permit(owner, accounts.bob, 300, 100, signature), Ok(()));
+            assert_eq!(erc20.allowance(owner, accounts.bob), 300);
+            assert_eq!(erc20.nonces(owner), 1);
+
+            // The nonce moved on, so the same signature cannot be replayed.
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 300, 100, signature),
+                Err(Error::InvalidSignature)
+            );
+        }
+
+        #[ink::test]
+        fn permit_rejects_wrong_signer() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let (_, owner) = permit_signer(7);
+            let (other_key, _) = permit_signer(8);
+
+            let signature = sign_permit(&other_key, erc20.permit_hash(owner, accounts.bob, 300, 0, 100));
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 300, 100, signature),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(erc20.allowance(owner, accounts.bob), 0);
+            assert_eq!(erc20.nonces(owner), 0);
+        }
+
+        #[ink::test]
+        fn permit_rejects_expired_deadline() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let (secret_key, owner) = permit_signer(7);
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(200);
+
+            let signature = sign_permit(&secret_key, erc20.permit_hash(owner, accounts.bob, 300, 0, 100));
+            assert_eq!(
+                erc20.permit(owner, accounts.bob, 300, 100, signature),
+                Err(Error::PermitExpired)
+            );
+        }
+
+        #[ink::test]
+        fn transfer_with_authorization_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let (secret_key, from) = permit_signer(7);
+            assert_eq!(erc20.transfer(from, 500), Ok(()));
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(50);
+
+            let nonce = [1; 32];
+            let signature = sign_permit(
+                &secret_key,
+                erc20.authorization_hash(from, accounts.bob, 300, 0, 100, nonce),
+            );
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                erc20.transfer_with_authorization(from, accounts.bob, 300, 0, 100, nonce, signature),
+                Ok(())
+            );
+            assert_eq!(erc20.balance_of(from), 200);
+            assert_eq!(erc20.balance_of(accounts.bob), 300);
+            assert!(erc20.authorization_state(from, nonce));
+
+            // Replaying the same authorization fails.
+            assert_eq!(
+                erc20.transfer_with_authorization(from, accounts.bob, 300, 0, 100, nonce, signature),
+                Err(Error::AuthorizationUsed)
+            );
+
+            // Changing the signed amount invalidates the signature.
+            let nonce = [2; 32];
+            let signature = sign_permit(
+                &secret_key,
+                erc20.authorization_hash(from, accounts.bob, 100, 0, 100, nonce),
+            );
+            assert_eq!(
+                erc20.transfer_with_authorization(from, accounts.bob, 200, 0, 100, nonce, signature),
+                Err(Error::InvalidSignature)
+            );
+            assert!(!erc20.authorization_state(from, nonce));
+        }
+
+        #[ink::test]
+        fn transfer_with_authorization_rejects_outside_window() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let (secret_key, from) = permit_signer(7);
+            assert_eq!(erc20.transfer(from, 500), Ok(()));
+            let nonce = [1; 32];
+            let signature = sign_permit(
+                &secret_key,
+                erc20.authorization_hash(from, accounts.bob, 300, 100, 200, nonce),
+            );
+
+            for timestamp in [100, 200, 250] {
+                ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(timestamp);
+                assert_eq!(
+                    erc20.transfer_with_authorization(from, accounts.bob, 300, 100, 200, nonce, signature),
+                    Err(Error::PermitExpired)
+                );
+            }
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+            assert!(!erc20.authorization_state(from, nonce));
+        }
+
+        #[ink::test]
+        fn packed_allowance_key_storage_cost() {
+            let mut erc20 = Erc20::new(100);
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let contract = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+
+            // The packed key is half the size of the encoded tuple key.
+            let tuple_key = (accounts.alice, accounts.bob);
+            let packed_key = Erc20::allowance_key(&accounts.alice, &accounts.bob);
+            assert_eq!(scale::Encode::encoded_size(&tuple_key), 64);
+            assert_eq!(scale::Encode::encoded_size(&packed_key), 32);
+            assert_ne!(packed_key, Erc20::allowance_key(&accounts.bob, &accounts.alice));
+
+            // Reading an allowance touches exactly one storage cell.
+            let (reads, writes) =
+                ink::env::test::get_contract_storage_rw::<ink::env::DefaultEnvironment>(&contract);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 0);
+            assert_eq!(
+                ink::env::test::get_contract_storage_rw::<ink::env::DefaultEnvironment>(&contract),
+                (reads + 1, writes)
+            );
+
+            // Approving stores one allowance cell next to the spender list.
+            let cells =
+                ink::env::test::count_used_storage_cells::<ink::env::DefaultEnvironment>(&contract)
+                    .expect("contract storage exists");
+            assert_eq!(erc20.approve(accounts.bob, 10), Ok(()));
+            assert_eq!(
+                ink::env::test::count_used_storage_cells::<ink::env::DefaultEnvironment>(&contract),
+                Ok(cells + 2)
+            );
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 10);
+        }
+
+        #[ink::test]
+        fn allowances_of_lists_active_spenders() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.allowances_of(accounts.alice), vec![]);
+
+            assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.increase_allowance(accounts.charlie, 200), Ok(()));
+            assert_eq!(
+                erc20.allowances_of(accounts.alice),
+                vec![(accounts.bob, 100), (accounts.charlie, 200)]
+            );
+
+            assert_eq!(erc20.decrease_allowance(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.allowances_of(accounts.alice), vec![(accounts.charlie, 200)]);
+            assert_eq!(erc20.approve(accounts.charlie, 0), Ok(()));
+            assert_eq!(erc20.allowances_of(accounts.alice), vec![]);
+        }
+
+        #[ink::test]
+        fn increasing_expired_allowance_clears_expiry() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(erc20.approve_with_expiry(accounts.bob, 300, 2000), Ok(()));
+            assert_eq!(erc20.increase_allowance(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.allowance_expiry(accounts.alice, accounts.bob), Some(2000));
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2001);
+            assert_eq!(erc20.increase_allowance(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.allowance_expiry(accounts.alice, accounts.bob), None);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 100);
+        }
+
+        #[ink::test]
+        fn expired_allowance_reads_as_zero() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(erc20.approve_with_expiry(accounts.bob, 300, 2000), Ok(()));
+            assert_eq!(erc20.allowance_expiry(accounts.alice, accounts.bob), Some(2000));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.charlie, 100), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 200);
+
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(2001);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 0);
+            assert_eq!(
+                erc20.transfer_from(accounts.alice, accounts.charlie, 100),
+                Err(Error::InsufficientAllowance)
+            );
+
+            // A plain approval has no expiry.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.approve(accounts.bob, 200), Ok(()));
+            assert_eq!(erc20.allowance_expiry(accounts.alice, accounts.bob), None);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 200);
+        }
+
+        #[ink::test]
+        fn approve_same_value_is_a_no_op() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            #[cfg(not(feature = "no-events"))]
+            let events_before = recorded_events().len();
+
+            assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
+            #[cfg(not(feature = "no-events"))]
+            let approvals = recorded_events()
+                .into_iter()
+                .skip(events_before)
+                .filter(|event| matches!(event, Event::Approval(_)))
+                .count();
+            #[cfg(not(feature = "no-events"))]
+            assert_eq!(approvals, 1);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 100);
+        }
+
+        #[ink::test]
+        fn is_unlimited_allowance_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert!(!erc20.is_unlimited_allowance(accounts.alice, accounts.bob));
+
+            assert_eq!(erc20.approve(accounts.bob, Balance::MAX), Ok(()));
+            assert!(erc20.is_unlimited_allowance(accounts.alice, accounts.bob));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.charlie, 10), Ok(()));
+            assert!(erc20.is_unlimited_allowance(accounts.alice, accounts.bob));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+
+            assert_eq!(erc20.approve(accounts.bob, Balance::MAX - 1), Ok(()));
+            assert!(!erc20.is_unlimited_allowance(accounts.alice, accounts.bob));
+        }
+
+        #[ink::test]
+        fn max_allowance_caps_approvals() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.max_allowance(), None);
+            assert_eq!(erc20.approve(accounts.bob, Balance::MAX), Ok(()));
+
+            assert_eq!(erc20.set_max_allowance(Some(500)), Ok(()));
+            assert_eq!(erc20.max_allowance(), Some(500));
+            assert_eq!(erc20.approve(accounts.bob, 501), Err(Error::AllowanceTooHigh));
+            assert_eq!(erc20.approve(accounts.bob, 500), Ok(()));
+            assert_eq!(erc20.increase_allowance(accounts.bob, 1), Err(Error::AllowanceTooHigh));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 500);
+            // Decreasing an allowance is never capped.
+            assert_eq!(erc20.decrease_allowance(accounts.bob, 100), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.set_max_allowance(None), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn self_balance_reads_contract_holdings() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.self_balance(), 0);
+
+            assert_eq!(erc20.transfer(accounts.frank, 250), Ok(()));
+            assert_eq!(erc20.self_balance(), 250);
+            assert_eq!(erc20.self_balance(), erc20.balance_of(accounts.frank));
+        }
+
+        #[ink::test]
+        fn combined_balance_works() {
+            let mut erc20 = Erc20::new(Balance::MAX);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.combined_balance(accounts.bob, accounts.charlie), 100);
+
+            // The whole supply split between two accounts sums to exactly the max.
+            assert_eq!(erc20.combined_balance(accounts.alice, accounts.bob), Balance::MAX);
+            assert_eq!(erc20.combined_balance(accounts.alice, accounts.alice), Balance::MAX);
+        }
+
+        #[ink::test]
+        fn allowances_batch_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.approve(accounts.django, 300), Ok(()));
+
+            assert_eq!(
+                erc20.allowances_batch(vec![
+                    (accounts.alice, accounts.django),
+                    (accounts.alice, accounts.charlie),
+                    (accounts.bob, accounts.alice),
+                    (accounts.alice, accounts.bob),
+                ]),
+                vec![300, 0, 0, 100]
+            );
+            assert_eq!(erc20.allowances_batch(vec![]), vec![]);
+        }
+
+        #[ink::test]
+        fn can_transfer_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert!(erc20.can_transfer(999));
+            assert!(erc20.can_transfer(1000));
+            assert!(!erc20.can_transfer(1001));
+
+            assert_eq!(erc20.set_fee(300, accounts.django), Ok(()));
+            assert!(erc20.can_transfer(1000));
+
+            assert_eq!(erc20.lock(accounts.alice, 400), Ok(()));
+            assert!(erc20.can_transfer(600));
+            assert!(!erc20.can_transfer(601));
+        }
+
+        #[ink::test]
+        fn transfer_up_to_moves_what_is_available() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.transfer_up_to(accounts.bob, 300), Ok(300));
+            assert_eq!(erc20.transfer_up_to(accounts.bob, 5000), Ok(700));
+            assert_eq!(erc20.balance_of(accounts.alice), 0);
+            assert_eq!(erc20.balance_of(accounts.bob), 1000);
+            #[cfg(not(feature = "no-events"))]
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::Transfer(Transfer { value: 700, .. }))
+            ));
+
+            let events_before = recorded_events().len();
+            assert_eq!(erc20.transfer_up_to(accounts.bob, 5000), Ok(0));
+            assert_eq!(recorded_events().len(), events_before);
+        }
+
+        #[ink::test]
+        fn transfer_up_to_respects_quota_but_not_cooldown() {
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            let mut erc20 = Erc20::new(1000);
+            assert_eq!(erc20.set_transfer_limit(100, 60), Ok(()));
+
+            assert_eq!(erc20.transfer_up_to(accounts.bob, 70), Ok(70));
+            assert_eq!(erc20.transfer_up_to(accounts.bob, 500), Ok(30));
+            assert_eq!(erc20.transfer_up_to(accounts.bob, 500), Ok(0));
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+
+            assert_eq!(erc20.set_transfer_limit(0, 0), Ok(()));
+            assert_eq!(erc20.set_transfer_cooldown(60), Ok(()));
+            assert_eq!(erc20.transfer_up_to(accounts.bob, 10), Ok(10));
+            assert_eq!(erc20.transfer_up_to(accounts.bob, 10), Err(Error::Cooldown));
+            assert_eq!(erc20.balance_of(accounts.bob), 110);
+        }
+
+        #[ink::test]
+        fn bool_variants_report_soft_failures() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.transfer_bool(accounts.bob, 100), Ok(true));
+            assert_eq!(erc20.transfer_bool(accounts.bob, 901), Ok(false));
+            assert_eq!(erc20.balance_of(accounts.alice), 900);
+            assert_eq!(erc20.approve_bool(accounts.charlie, 950), Ok(true));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            // Enough allowance but not enough balance: the allowance is kept.
+            assert_eq!(
+                erc20.transfer_from_bool(accounts.alice, accounts.bob, 901),
+                Ok(false)
+            );
+            assert_eq!(erc20.allowance(accounts.alice, accounts.charlie), 950);
+            assert_eq!(
+                erc20.transfer_from_bool(accounts.bob, accounts.charlie, 1),
+                Ok(false)
+            );
+            assert_eq!(
+                erc20.transfer_from_bool(accounts.alice, accounts.bob, 900),
+                Ok(true)
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 1000);
+            assert_eq!(erc20.allowance(accounts.alice, accounts.charlie), 50);
+
+            // Hard failures still surface as errors.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.freeze(accounts.django), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                erc20.transfer_bool(accounts.django, 1),
+                Err(Error::AccountFrozen)
+            );
+        }
+
+        #[ink::test]
+        fn balance_and_allowance_matches_getters() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.approve(accounts.bob, 300), Ok(()));
+
+            assert_eq!(
+                erc20.balance_and_allowance(accounts.alice, accounts.bob),
+                (
+                    erc20.balance_of(accounts.alice),
+                    erc20.allowance(accounts.alice, accounts.bob)
+                )
+            );
+            assert_eq!(erc20.balance_and_allowance(accounts.alice, accounts.bob), (1000, 300));
+            assert_eq!(erc20.balance_and_allowance(accounts.bob, accounts.alice), (0, 0));
+        }
 
-            assert_eq!(erc20.transfer_from(accounts.alice, accounts.bob, 1000), Ok(()));
-            assert_eq!(erc20.balance_of(accounts.bob), 1100);
-            assert_eq!(erc20.balance_of(accounts.alice), total_supply -100 -1000);
-            assert_eq!(erc20.allowance(accounts.alice, accounts.charlie), 4000);
+        #[ink::test]
+        fn spendable_is_capped_by_balance() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.spendable(accounts.alice, accounts.bob), 0);
+
+            // Allowance above the balance: the balance is the limit.
+            assert_eq!(erc20.approve(accounts.bob, 5000), Ok(()));
+            assert_eq!(erc20.spendable(accounts.alice, accounts.bob), 1000);
+
+            // Allowance below the balance: the allowance is the limit.
+            assert_eq!(erc20.approve(accounts.bob, 300), Ok(()));
+            assert_eq!(erc20.spendable(accounts.alice, accounts.bob), 300);
+        }
+
+        #[ink::test]
+        fn transfer_and_call_fails_before_callback_without_balance() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(
+                erc20.transfer_and_call(accounts.bob, 1001, vec![]),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(erc20.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn safe_transfer_to_account_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.safe_transfer(accounts.bob, 100, vec![1]), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.bob), 100);
+            assert_eq!(
+                erc20.safe_transfer(accounts.bob, 1000, vec![]),
+                Err(Error::InsufficientBalance)
+            );
+        }
+
+        #[ink::test]
+        fn increase_allowance_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.increase_allowance(accounts.bob, 50), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 150);
+
+            assert_eq!(
+                erc20.increase_allowance(accounts.bob, Balance::MAX),
+                Err(Error::AllowanceOverflow)
+            );
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 150);
+        }
+
+        #[ink::test]
+        fn decrease_allowance_works() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.decrease_allowance(accounts.bob, 40), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 60);
+
+            assert_eq!(
+                erc20.decrease_allowance(accounts.bob, 61),
+                Err(Error::InsufficientAllowance)
+            );
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 60);
+        }
+
+        #[ink::test]
+        fn decrease_allowance_saturating_clamps_to_zero() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.approve(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.decrease_allowance_saturating(accounts.bob, 40), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 60);
+
+            assert_eq!(erc20.decrease_allowance_saturating(accounts.bob, 61), Ok(()));
+            assert_eq!(erc20.allowance(accounts.alice, accounts.bob), 0);
+            #[cfg(not(feature = "no-events"))]
+            assert!(matches!(
+                recorded_events().last(),
+                Some(Event::Approval(Approval { value: 0, .. }))
+            ));
+        }
+
+        #[ink::test]
+        fn arithmetic_helpers_return_errors() {
+            assert_eq!(Erc20::add(1, 2), Ok(3));
+            assert_eq!(Erc20::add(Balance::MAX, 1), Err(Error::ArithmeticOverflow));
+            assert_eq!(Erc20::sub(3, 2), Ok(1));
+            assert_eq!(Erc20::sub(0, 1), Err(Error::ArithmeticOverflow));
+        }
+
+        #[ink::test]
+        fn votes_overflow_returns_error() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.delegate(accounts.bob), Ok(()));
+            assert_eq!(erc20.get_votes(accounts.bob), 1000);
+
+            // Moving more votes away than the delegate holds used to underflow.
+            assert_eq!(
+                erc20.move_votes(Some(accounts.bob), None, 1001),
+                Err(Error::ArithmeticOverflow)
+            );
+            assert_eq!(
+                erc20.move_votes(None, Some(accounts.bob), Balance::MAX),
+                Err(Error::ArithmeticOverflow)
+            );
+            assert_eq!(erc20.get_votes(accounts.bob), 1000);
+        }
+
+        #[cfg(not(feature = "no-events"))]
+        #[ink::test]
+        fn every_allowance_change_emits_one_approval() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.grant_role(MINTER_ROLE, accounts.bob), Ok(()));
+            let approvals = || {
+                recorded_events()
+                    .into_iter()
+                    .filter_map(|event| match event {
+                        Event::Approval(Approval { value, .. }) => Some(value),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(erc20.approve(accounts.bob, 500), Ok(()));
+            assert_eq!(erc20.increase_allowance(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.decrease_allowance(accounts.bob, 50), Ok(()));
+            assert_eq!(erc20.approve_with_expiry(accounts.bob, 400, 10_000), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(erc20.transfer_from(accounts.alice, accounts.charlie, 100), Ok(()));
+            assert_eq!(erc20.burn_from(accounts.alice, 100), Ok(()));
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(erc20.decrease_allowance_saturating(accounts.bob, 1000), Ok(()));
+            assert_eq!(approvals(), vec![500, 600, 550, 400, 300, 200, 0]);
+
+            // Failed changes emit nothing.
+            assert_eq!(erc20.decrease_allowance(accounts.bob, 1), Err(Error::InsufficientAllowance));
+            assert_eq!(approvals().len(), 7);
+        }
+
+        #[cfg(not(feature = "no-events"))]
+        #[ink::test]
+        fn transfer_events_are_sequenced() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.transfer(accounts.bob, 100), Ok(()));
+            assert_eq!(erc20.transfer(accounts.charlie, 100), Ok(()));
+            assert_eq!(erc20.transfer(accounts.django, 100), Ok(()));
+
+            let seqs: Vec<u64> = recorded_events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::Transfer(Transfer { seq, .. }) => Some(seq),
+                    _ => None,
+                })
+                .collect();
+            // The constructor's mint is `0`.
+            assert_eq!(seqs, vec![0, 1, 2, 3]);
+        }
+
+        #[cfg(not(feature = "no-events"))]
+        #[ink::test]
+        fn zero_supply_deploy_starts_sequence_at_first_transfer() {
+            let mut erc20 = Erc20::new(0);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            assert_eq!(erc20.mint(accounts.bob, 100), Ok(()));
+
+            let seqs: Vec<u64> = recorded_events()
+                .into_iter()
+                .filter_map(|event| match event {
+                    Event::Transfer(Transfer { seq, .. }) => Some(seq),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(seqs, vec![0]);
+        }
+
+        #[ink::test]
+        fn self_transfer_keeps_balance() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            assert_eq!(erc20.transfer(accounts.alice, 100), Ok(()));
+            assert_eq!(erc20.balance_of(accounts.alice), 1000);
+            assert_eq!(erc20.transfer(accounts.alice, 1001), Err(Error::InsufficientBalance));
+        }
+
+        #[cfg(not(feature = "no-events"))]
+        #[ink::test]
+        fn zero_value_transfer_emits_single_event() {
+            let mut erc20 = Erc20::new(1000);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let events_before = recorded_events().len();
+
+            assert_eq!(erc20.transfer(accounts.bob, 0), Ok(()));
+            let events = recorded_events();
+            // One `Transfer`, preceded by its `TransferMeta`.
+            assert_eq!(events.len(), events_before + 2);
+            assert!(matches!(
+                events.last(),
+                Some(Event::Transfer(Transfer { to, value: 0, .. })) if *to == accounts.bob
+            ));
+            assert_eq!(erc20.balance_of(accounts.alice), 1000);
+
+            assert_eq!(erc20.freeze(accounts.bob), Ok(()));
+            assert_eq!(erc20.transfer(accounts.bob, 0), Err(Error::AccountFrozen));
+        }
+
+        #[ink::test]
+        fn transfer_overflow_returns_error() {
+            let mut erc20 = Erc20::new(0);
+            let accounts =
+                ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            // Seed balances directly, `mint` would reject a supply this large.
+            erc20.balances.insert(accounts.alice, &(Balance::MAX - 1));
+            erc20.balances.insert(accounts.bob, &(Balance::MAX - 1));
+
+            assert_eq!(erc20.transfer(accounts.bob, 2), Err(Error::BalanceOverflow));
+            assert_eq!(erc20.balance_of(accounts.alice), Balance::MAX - 1);
+            assert_eq!(erc20.balance_of(accounts.bob), Balance::MAX - 1);
         }
     }
 
@@ -199,6 +5639,8 @@ mod erc20 {
     ///
     /// When running these you need to make sure that you:
     /// - Compile the tests with the `e2e-tests` feature flag enabled (`--features e2e-tests`)
+    /// - Have the `rust-src` component installed, since the contract and the mocks
+    ///   are built for Wasm with `-Zbuild-std` when the tests compile
     /// - Are running a Substrate node which contains `pallet-contracts` in the background
     #[cfg(all(test, feature = "e2e-tests"))]
     mod e2e_tests {
@@ -225,43 +5667,502 @@ mod erc20 {
                 .account_id;
 
             // Then
-            let get = build_message::<Erc20Ref>(contract_account_id.clone())
-                .call(|erc20| erc20.get());
-            let get_result = client.call_dry_run(&ink_e2e::alice(), &get, 0, None).await;
-            assert!(matches!(get_result.return_value(), false));
+            let total_supply = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| PSP22::total_supply(erc20));
+            let total_supply_result = client
+                .call_dry_run(&ink_e2e::alice(), &total_supply, 0, None)
+                .await;
+            assert_eq!(total_supply_result.return_value(), 0);
 
             Ok(())
         }
 
-        /// We test that we can read and write a value from the on-chain contract contract.
+        /// We test that balances can be read and moved on the on-chain contract.
         #[ink_e2e::test]
         async fn it_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
             // Given
-            let constructor = Erc20Ref::new(false);
+            let constructor = Erc20Ref::new(1000);
             let contract_account_id = client
                 .instantiate("erc20", &ink_e2e::bob(), constructor, 0, None)
                 .await
                 .expect("instantiate failed")
                 .account_id;
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+
+            let balance_of = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| PSP22::balance_of(erc20, bob_account));
+            let balance_result = client.call_dry_run(&ink_e2e::bob(), &balance_of, 0, None).await;
+            assert_eq!(balance_result.return_value(), 1000);
+
+            // When
+            let transfer = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| PSP22::transfer(erc20, charlie_account, 400));
+            let _transfer_result = client
+                .call(&ink_e2e::bob(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            // Then
+            let balance_of = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| PSP22::balance_of(erc20, bob_account));
+            let balance_result = client.call_dry_run(&ink_e2e::bob(), &balance_of, 0, None).await;
+            assert_eq!(balance_result.return_value(), 600);
+
+            let balance_of = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| PSP22::balance_of(erc20, charlie_account));
+            let balance_result = client.call_dry_run(&ink_e2e::bob(), &balance_of, 0, None).await;
+            assert_eq!(balance_result.return_value(), 400);
+
+            Ok(())
+        }
+
+        /// We test that the token can be driven through the `PSP22` trait interface.
+        #[ink_e2e::test]
+        async fn psp22_transfer_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let constructor = Erc20Ref::new(1000);
+            let contract_account_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+
+            // When
+            let transfer = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| PSP22::transfer(erc20, bob_account, 100));
+            let _transfer_result = client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            // Then
+            let balance_of = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| PSP22::balance_of(erc20, bob_account));
+            let balance_result = client.call_dry_run(&ink_e2e::alice(), &balance_of, 0, None).await;
+            assert_eq!(balance_result.return_value(), 100);
+
+            Ok(())
+        }
+
+        /// We test that native value can be wrapped into tokens and unwrapped again.
+        #[ink_e2e::test]
+        async fn deposit_and_withdraw_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let constructor = Erc20Ref::new(0);
+            let contract_account_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
 
-            let get = build_message::<Erc20Ref>(contract_account_id.clone())
-                .call(|erc20| erc20.get());
-            let get_result = client.call_dry_run(&ink_e2e::bob(), &get, 0, None).await;
-            assert!(matches!(get_result.return_value(), false));
+            // When
+            let deposit = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| erc20.deposit());
+            client
+                .call(&ink_e2e::bob(), deposit, 1_000, None)
+                .await
+                .expect("deposit failed");
+            let withdraw = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| erc20.withdraw(400));
+            client
+                .call(&ink_e2e::bob(), withdraw, 0, None)
+                .await
+                .expect("withdraw failed");
+
+            // Then
+            let balance_of = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| erc20.balance_of(bob_account));
+            let balance_result = client.call_dry_run(&ink_e2e::bob(), &balance_of, 0, None).await;
+            assert_eq!(balance_result.return_value(), 600);
+            let contract_balance = client
+                .balance(contract_account_id)
+                .await
+                .expect("failed to get contract balance");
+            assert!(contract_balance >= 600);
+
+            Ok(())
+        }
+
+        /// We test that `transfer_and_call` notifies the receiving contract.
+        #[ink_e2e::test(additional_contracts = "mocks/token-receiver/Cargo.toml")]
+        async fn transfer_and_call_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let constructor = Erc20Ref::new(1000);
+            let erc20_account_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let receiver_constructor = token_receiver::TokenReceiverMockRef::new(true);
+            let receiver_account_id = client
+                .instantiate("token_receiver", &ink_e2e::alice(), receiver_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let alice_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+
+            // When
+            let transfer_and_call = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.transfer_and_call(receiver_account_id.clone(), 100, vec![7]));
+            client
+                .call(&ink_e2e::alice(), transfer_and_call, 0, None)
+                .await
+                .expect("transfer_and_call failed");
+
+            // Then
+            let balance_of = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.balance_of(receiver_account_id.clone()));
+            let balance_result = client.call_dry_run(&ink_e2e::alice(), &balance_of, 0, None).await;
+            assert_eq!(balance_result.return_value(), 100);
+            let last_received = build_message::<token_receiver::TokenReceiverMockRef>(receiver_account_id.clone())
+                .call(|receiver| receiver.last_received());
+            let last_received_result = client.call_dry_run(&ink_e2e::alice(), &last_received, 0, None).await;
+            assert_eq!(last_received_result.return_value(), Some((alice_account, 100, vec![7])));
+
+            Ok(())
+        }
+
+        /// We test that `safe_transfer` only delivers to contracts accepting tokens.
+        #[ink_e2e::test(additional_contracts = "mocks/token-receiver/Cargo.toml")]
+        async fn safe_transfer_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let constructor = Erc20Ref::new(1000);
+            let erc20_account_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let accepting_constructor = token_receiver::TokenReceiverMockRef::new(true);
+            let accepting_account_id = client
+                .instantiate("token_receiver", &ink_e2e::alice(), accepting_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let rejecting_constructor = token_receiver::TokenReceiverMockRef::new(false);
+            let rejecting_account_id = client
+                .instantiate("token_receiver", &ink_e2e::alice(), rejecting_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            // When
+            let safe_transfer = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.safe_transfer(accepting_account_id.clone(), 100, vec![]));
+            client
+                .call(&ink_e2e::alice(), safe_transfer, 0, None)
+                .await
+                .expect("safe_transfer failed");
+            let safe_transfer = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.safe_transfer(rejecting_account_id.clone(), 100, vec![]));
+            let rejected_result = client.call_dry_run(&ink_e2e::alice(), &safe_transfer, 0, None).await;
+
+            // Then
+            assert_eq!(rejected_result.return_value(), Err(Error::NonReceiver));
+            let balance_of = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.balance_of(accepting_account_id.clone()));
+            let balance_result = client.call_dry_run(&ink_e2e::alice(), &balance_of, 0, None).await;
+            assert_eq!(balance_result.return_value(), 100);
+            let balance_of = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.balance_of(rejecting_account_id.clone()));
+            let balance_result = client.call_dry_run(&ink_e2e::alice(), &balance_of, 0, None).await;
+            assert_eq!(balance_result.return_value(), 0);
+
+            Ok(())
+        }
+
+        /// We test that upgrading the code keeps balances and exposes new messages.
+        #[ink_e2e::test(additional_contracts = "mocks/erc20-v2/Cargo.toml")]
+        async fn set_code_hash_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let constructor = Erc20Ref::new(1000);
+            let contract_account_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let transfer = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| erc20.transfer(bob_account, 100));
+            client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+            let code_hash = client
+                .upload("erc20_v2", &ink_e2e::alice(), None)
+                .await
+                .expect("upload failed")
+                .code_hash;
+
+            // When
+            let set_code_hash = build_message::<Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| erc20.set_code_hash(code_hash.as_ref().try_into().expect("32 byte hash")));
+            client
+                .call(&ink_e2e::alice(), set_code_hash, 0, None)
+                .await
+                .expect("set_code_hash failed");
+
+            // Then
+            let version = build_message::<erc20_v2::Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| erc20.version());
+            let version_result = client.call_dry_run(&ink_e2e::alice(), &version, 0, None).await;
+            assert_eq!(version_result.return_value(), 2);
+            let balance_of = build_message::<erc20_v2::Erc20Ref>(contract_account_id.clone())
+                .call(|erc20| erc20.balance_of(bob_account));
+            let balance_result = client.call_dry_run(&ink_e2e::alice(), &balance_of, 0, None).await;
+            assert_eq!(balance_result.return_value(), 100);
+
+            Ok(())
+        }
+
+        /// We test that a flash loan is repaid by a borrower holding the fee
+        /// and reverted for borrowers that reject it or cannot repay.
+        #[ink_e2e::test(additional_contracts = "mocks/flash-borrower/Cargo.toml")]
+        async fn flash_loan_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let constructor = Erc20Ref::new(1000);
+            let erc20_account_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let set_flash_fee = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.set_flash_fee(100));
+            client
+                .call(&ink_e2e::alice(), set_flash_fee, 0, None)
+                .await
+                .expect("set_flash_fee failed");
+            // Bob acts as the pool the borrowers trade with; Charlie, who
+            // approves nothing, leaves his borrower unable to repay.
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+            let mut borrowers = Vec::new();
+            for (pool, accept) in [(bob_account.clone(), true), (bob_account.clone(), false), (charlie_account, true)] {
+                let borrower_constructor =
+                    flash_borrower::FlashBorrowerMockRef::new(erc20_account_id.clone(), pool, accept);
+                let borrower_account_id = client
+                    .instantiate("flash_borrower", &ink_e2e::alice(), borrower_constructor, 0, None)
+                    .await
+                    .expect("instantiate failed")
+                    .account_id;
+                borrowers.push(borrower_account_id);
+            }
+            let (repaying, rejecting, broke) = (borrowers[0].clone(), borrowers[1].clone(), borrowers[2].clone());
+            // The pool pays the borrower's profit, covering the fee.
+            let transfer = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.transfer(bob_account.clone(), 10));
+            client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+            let approve = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.approve(repaying.clone(), 1010));
+            client
+                .call(&ink_e2e::bob(), approve, 0, None)
+                .await
+                .expect("approve failed");
+
+            // When
+            let flash_loan = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.flash_loan(repaying.clone(), 1000, vec![]));
+            client
+                .call(&ink_e2e::alice(), flash_loan, 0, None)
+                .await
+                .expect("flash_loan failed");
+            let flash_loan = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.flash_loan(rejecting.clone(), 1000, vec![]));
+            let rejected_result = client.call_dry_run(&ink_e2e::alice(), &flash_loan, 0, None).await;
+            let flash_loan = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.flash_loan(broke.clone(), 1000, vec![]));
+            let unpaid_result = client.call_dry_run(&ink_e2e::alice(), &flash_loan, 0, None).await;
+
+            // Then
+            assert_eq!(rejected_result.return_value(), Err(Error::CallbackFailed));
+            assert_eq!(unpaid_result.return_value(), Err(Error::InsufficientBalance));
+            let last_loan = build_message::<flash_borrower::FlashBorrowerMockRef>(repaying.clone())
+                .call(|borrower| borrower.last_loan());
+            let last_loan_result = client.call_dry_run(&ink_e2e::alice(), &last_loan, 0, None).await;
+            assert_eq!(last_loan_result.return_value(), Some((1000, 10)));
+            let balance_of = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.balance_of(repaying.clone()));
+            let balance_result = client.call_dry_run(&ink_e2e::alice(), &balance_of, 0, None).await;
+            assert_eq!(balance_result.return_value(), 0);
+            let balance_of = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.balance_of(bob_account.clone()));
+            let pool_balance_result = client.call_dry_run(&ink_e2e::alice(), &balance_of, 0, None).await;
+            assert_eq!(pool_balance_result.return_value(), 0);
+            let total_supply = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.total_supply());
+            let total_supply_result = client.call_dry_run(&ink_e2e::alice(), &total_supply, 0, None).await;
+            assert_eq!(total_supply_result.return_value(), 990);
+
+            Ok(())
+        }
+
+        /// We test that a spender can pull approved tokens from its `approve_and_call` callback.
+        #[ink_e2e::test(additional_contracts = "mocks/approval-spender/Cargo.toml")]
+        async fn approve_and_call_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let constructor = Erc20Ref::new(1000);
+            let erc20_account_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let spender_constructor = approval_spender::ApprovalSpenderMockRef::new(erc20_account_id.clone());
+            let spender_account_id = client
+                .instantiate("approval_spender", &ink_e2e::alice(), spender_constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+
+            // When
+            let approve_and_call = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.approve_and_call(spender_account_id.clone(), 300, vec![]));
+            client
+                .call(&ink_e2e::alice(), approve_and_call, 0, None)
+                .await
+                .expect("approve_and_call failed");
+            let approve_and_call = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.approve_and_call(bob_account.clone(), 300, vec![]));
+            let rejected_result = client.call_dry_run(&ink_e2e::alice(), &approve_and_call, 0, None).await;
+
+            // Then
+            assert_eq!(rejected_result.return_value(), Err(Error::CallbackFailed));
+            let deposited = build_message::<approval_spender::ApprovalSpenderMockRef>(spender_account_id.clone())
+                .call(|spender| spender.deposited());
+            let deposited_result = client.call_dry_run(&ink_e2e::alice(), &deposited, 0, None).await;
+            assert_eq!(deposited_result.return_value(), 300);
+            let balance_of = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.balance_of(spender_account_id.clone()));
+            let balance_result = client.call_dry_run(&ink_e2e::alice(), &balance_of, 0, None).await;
+            assert_eq!(balance_result.return_value(), 300);
+            let alice_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let allowance = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.allowance(alice_account.clone(), spender_account_id.clone()));
+            let allowance_result = client.call_dry_run(&ink_e2e::alice(), &allowance, 0, None).await;
+            assert_eq!(allowance_result.return_value(), 0);
+            // The spender's `transfer_from` updated the holder count, which
+            // must survive the outer message.
+            let holder_count = build_message::<Erc20Ref>(erc20_account_id.clone())
+                .call(|erc20| erc20.holder_count());
+            let holder_count_result = client.call_dry_run(&ink_e2e::alice(), &holder_count, 0, None).await;
+            assert_eq!(holder_count_result.return_value(), 2);
+
+            Ok(())
+        }
+
+        /// We test that holders can swap their old tokens exactly once.
+        #[ink_e2e::test]
+        async fn migrate_from_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let old_token = client
+                .instantiate("erc20", &ink_e2e::alice(), Erc20Ref::new(1000), 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let new_token = client
+                .instantiate("erc20", &ink_e2e::alice(), Erc20Ref::new(0), 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let transfer = build_message::<Erc20Ref>(old_token.clone())
+                .call(|erc20| erc20.transfer(bob_account, 300));
+            client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+            let set_migration_source = build_message::<Erc20Ref>(new_token.clone())
+                .call(|erc20| erc20.set_migration_source(Some(old_token.clone())));
+            client
+                .call(&ink_e2e::alice(), set_migration_source, 0, None)
+                .await
+                .expect("set_migration_source failed");
+            let approve = build_message::<Erc20Ref>(old_token.clone())
+                .call(|erc20| erc20.approve(new_token.clone(), 300));
+            client
+                .call(&ink_e2e::bob(), approve, 0, None)
+                .await
+                .expect("approve failed");
+
+            // When
+            let migrate = build_message::<Erc20Ref>(new_token.clone())
+                .call(|erc20| erc20.migrate_from(old_token.clone(), bob_account));
+            let migrate_result = client
+                .call(&ink_e2e::bob(), migrate, 0, None)
+                .await
+                .expect("migrate_from failed");
+            let migrate = build_message::<Erc20Ref>(new_token.clone())
+                .call(|erc20| erc20.migrate_from(old_token.clone(), bob_account));
+            let repeated_result = client.call_dry_run(&ink_e2e::bob(), &migrate, 0, None).await;
+
+            // Then
+            assert_eq!(migrate_result.return_value(), Ok(300));
+            assert_eq!(repeated_result.return_value(), Err(Error::AlreadyMigrated));
+            let balance_of = build_message::<Erc20Ref>(new_token.clone())
+                .call(|erc20| erc20.balance_of(bob_account));
+            let balance_result = client.call_dry_run(&ink_e2e::alice(), &balance_of, 0, None).await;
+            assert_eq!(balance_result.return_value(), 300);
+            let total_supply = build_message::<Erc20Ref>(new_token.clone())
+                .call(|erc20| erc20.total_supply());
+            let total_supply_result = client.call_dry_run(&ink_e2e::alice(), &total_supply, 0, None).await;
+            assert_eq!(total_supply_result.return_value(), 300);
+            // The old tokens are held by the new token and cannot be migrated again.
+            let old_balance_of = build_message::<Erc20Ref>(old_token.clone())
+                .call(|erc20| erc20.balance_of(bob_account));
+            let old_balance_result = client.call_dry_run(&ink_e2e::alice(), &old_balance_of, 0, None).await;
+            assert_eq!(old_balance_result.return_value(), 0);
+            let old_balance_of = build_message::<Erc20Ref>(old_token.clone())
+                .call(|erc20| erc20.balance_of(new_token.clone()));
+            let old_balance_result = client.call_dry_run(&ink_e2e::alice(), &old_balance_of, 0, None).await;
+            assert_eq!(old_balance_result.return_value(), 300);
+
+            Ok(())
+        }
+
+        /// We test that tokens of another PSP22 sent to the contract can be reclaimed.
+        #[ink_e2e::test]
+        async fn reclaim_token_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            // Given
+            let token_a = client
+                .instantiate("erc20", &ink_e2e::alice(), Erc20Ref::new(1000), 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let token_b = client
+                .instantiate("erc20", &ink_e2e::alice(), Erc20Ref::new(1000), 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+            let bob_account = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let mistaken_transfer = build_message::<Erc20Ref>(token_b.clone())
+                .call(|erc20| erc20.transfer(token_a.clone(), 300));
+            client
+                .call(&ink_e2e::alice(), mistaken_transfer, 0, None)
+                .await
+                .expect("transfer failed");
 
             // When
-            let flip = build_message::<Erc20Ref>(contract_account_id.clone())
-                .call(|erc20| erc20.flip());
-            let _flip_result = client
-                .call(&ink_e2e::bob(), flip, 0, None)
+            let reclaim = build_message::<Erc20Ref>(token_a.clone())
+                .call(|erc20| erc20.reclaim_token(token_b.clone(), bob_account));
+            client
+                .call(&ink_e2e::alice(), reclaim, 0, None)
                 .await
-                .expect("flip failed");
+                .expect("reclaim_token failed");
 
             // Then
-            let get = build_message::<Erc20Ref>(contract_account_id.clone())
-                .call(|erc20| erc20.get());
-            let get_result = client.call_dry_run(&ink_e2e::bob(), &get, 0, None).await;
-            assert!(matches!(get_result.return_value(), true));
+            let balance_of_a = build_message::<Erc20Ref>(token_b.clone())
+                .call(|erc20| erc20.balance_of(token_a.clone()));
+            let balance_result = client.call_dry_run(&ink_e2e::alice(), &balance_of_a, 0, None).await;
+            assert_eq!(balance_result.return_value(), 0);
+            let balance_of_bob = build_message::<Erc20Ref>(token_b.clone())
+                .call(|erc20| erc20.balance_of(bob_account));
+            let balance_result = client.call_dry_run(&ink_e2e::alice(), &balance_of_bob, 0, None).await;
+            assert_eq!(balance_result.return_value(), 300);
 
             Ok(())
         }
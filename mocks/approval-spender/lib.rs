@@ -0,0 +1,69 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::approval_spender::{ApprovalSpenderMock, ApprovalSpenderMockRef};
+
+/// An `ApprovalReceiver` used by the erc20 end-to-end tests.
+///
+/// When notified of an approval it immediately pulls the approved tokens to
+/// itself through `PSP22::transfer_from`, like a staking contract taking a
+/// deposit, and only accepts the approval if that succeeded.
+#[ink::contract]
+mod approval_spender {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::CallFlags;
+    use ink::prelude::vec::Vec;
+
+    /// Mirrors `erc20::ApprovalReceiver`; the trait and message names
+    /// determine the selector the token calls.
+    #[ink::trait_definition]
+    pub trait ApprovalReceiver {
+        #[ink(message)]
+        fn on_approval_received(&mut self, owner: AccountId, value: Balance, data: Vec<u8>) -> [u8; 4];
+    }
+
+    /// Value returned to accept an approval, see `erc20::ON_APPROVAL_RECEIVED`.
+    const ON_APPROVAL_RECEIVED: [u8; 4] = ink::selector_bytes!("ApprovalReceiver::on_approval_received");
+
+    #[ink(storage)]
+    pub struct ApprovalSpenderMock {
+        token: AccountId,
+        deposited: Balance,
+    }
+
+    impl ApprovalSpenderMock {
+        #[ink(constructor)]
+        pub fn new(token: AccountId) -> Self {
+            Self { token, deposited: 0 }
+        }
+
+        /// Returns the total amount pulled in so far.
+        #[ink(message)]
+        pub fn deposited(&self) -> Balance {
+            self.deposited
+        }
+    }
+
+    impl ApprovalReceiver for ApprovalSpenderMock {
+        #[ink(message)]
+        fn on_approval_received(&mut self, owner: AccountId, value: Balance, _data: Vec<u8>) -> [u8; 4] {
+            // The token's errors encode as a single variant index.
+            let result = build_call::<Environment>()
+                .call(self.token)
+                .gas_limit(0)
+                .call_flags(CallFlags::default().set_allow_reentry(true))
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer_from")))
+                        .push_arg(owner)
+                        .push_arg(self.env().account_id())
+                        .push_arg(value),
+                )
+                .returns::<Result<(), u8>>()
+                .try_invoke();
+            if !matches!(result, Ok(Ok(Ok(())))) {
+                return [0; 4];
+            }
+            self.deposited += value;
+            ON_APPROVAL_RECEIVED
+        }
+    }
+}
@@ -0,0 +1,48 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::erc20_v2::{Erc20, Erc20Ref};
+
+/// An upgraded erc20 code used by the `set_code_hash` end-to-end test.
+///
+/// It only exposes read-only messages so that the fields it leaves out of the
+/// original storage layout are never written back.
+#[ink::contract]
+mod erc20_v2 {
+    use ink::storage::Mapping;
+
+    /// Leading fields of `erc20::Erc20`. Storage keys are derived from the
+    /// struct and field names, so both must match the original contract.
+    #[ink(storage)]
+    pub struct Erc20 {
+        total_supply: Balance,
+        balances: Mapping<AccountId, Balance>,
+    }
+
+    impl Erc20 {
+        #[ink(constructor)]
+        pub fn new(total_supply: Balance) -> Self {
+            let mut balances = Mapping::default();
+            balances.insert(Self::env().caller(), &total_supply);
+            Self {
+                total_supply,
+                balances,
+            }
+        }
+
+        #[ink(message)]
+        pub fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or_default()
+        }
+
+        /// Only available after the upgrade.
+        #[ink(message)]
+        pub fn version(&self) -> u32 {
+            2
+        }
+    }
+}
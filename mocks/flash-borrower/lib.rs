@@ -0,0 +1,92 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::flash_borrower::{FlashBorrowerMock, FlashBorrowerMockRef};
+
+/// A `FlashBorrower` used by the erc20 end-to-end tests.
+///
+/// It records the last loan it was offered and, if instantiated to accept
+/// loans, puts the loaned tokens to work like an arbitrage would: it sends
+/// them to `pool` and pulls `amount + fee` back through `PSP22::transfer_from`,
+/// relying on `pool` having approved it. Whatever comes back is repaid.
+#[ink::contract]
+mod flash_borrower {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::CallFlags;
+    use ink::prelude::vec::Vec;
+
+    /// Mirrors `erc20::FlashBorrower`; the trait and message names determine
+    /// the selector the token calls.
+    #[ink::trait_definition]
+    pub trait FlashBorrower {
+        #[ink(message)]
+        fn on_flash_loan(&mut self, amount: Balance, fee: Balance, data: Vec<u8>) -> [u8; 4];
+    }
+
+    /// Value returned to accept a loan, see `erc20::ON_FLASH_LOAN`.
+    const ON_FLASH_LOAN: [u8; 4] = ink::selector_bytes!("FlashBorrower::on_flash_loan");
+
+    #[ink(storage)]
+    pub struct FlashBorrowerMock {
+        token: AccountId,
+        pool: AccountId,
+        accept: bool,
+        last_loan: Option<(Balance, Balance)>,
+    }
+
+    impl FlashBorrowerMock {
+        #[ink(constructor)]
+        pub fn new(token: AccountId, pool: AccountId, accept: bool) -> Self {
+            Self {
+                token,
+                pool,
+                accept,
+                last_loan: None,
+            }
+        }
+
+        /// Returns `(amount, fee)` of the last loan offered.
+        #[ink(message)]
+        pub fn last_loan(&self) -> Option<(Balance, Balance)> {
+            self.last_loan
+        }
+
+        /// Sends `input` to the token and returns whether it succeeded. The
+        /// token's errors encode as a single variant index.
+        fn call_token<Args: scale::Encode>(&self, input: ExecutionInput<Args>) -> bool {
+            let result = build_call::<Environment>()
+                .call(self.token)
+                .gas_limit(0)
+                .call_flags(CallFlags::default().set_allow_reentry(true))
+                .exec_input(input)
+                .returns::<Result<(), u8>>()
+                .try_invoke();
+            matches!(result, Ok(Ok(Ok(()))))
+        }
+    }
+
+    impl FlashBorrower for FlashBorrowerMock {
+        #[ink(message)]
+        fn on_flash_loan(&mut self, amount: Balance, fee: Balance, _data: Vec<u8>) -> [u8; 4] {
+            self.last_loan = Some((amount, fee));
+            if !self.accept {
+                return [0; 4];
+            }
+            let sent = self.call_token(
+                ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer")))
+                    .push_arg(self.pool)
+                    .push_arg(amount),
+            );
+            if !sent {
+                return [0; 4];
+            }
+            // A pool that did not approve enough leaves the loan unrepaid.
+            self.call_token(
+                ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer_from")))
+                    .push_arg(self.pool)
+                    .push_arg(self.env().account_id())
+                    .push_arg(amount + fee),
+            );
+            ON_FLASH_LOAN
+        }
+    }
+}
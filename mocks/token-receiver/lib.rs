@@ -0,0 +1,59 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::token_receiver::{TokenReceiverMock, TokenReceiverMockRef};
+
+/// A `TokenReceiver` used by the erc20 end-to-end tests.
+///
+/// It records the last callback it received and either accepts or rejects
+/// tokens depending on how it was instantiated.
+#[ink::contract]
+mod token_receiver {
+    use ink::prelude::vec::Vec;
+
+    /// `(from, value, data)` of a received callback.
+    type Received = (AccountId, Balance, Vec<u8>);
+
+    /// Mirrors `erc20::TokenReceiver`; the trait and message names determine
+    /// the selector the token calls.
+    #[ink::trait_definition]
+    pub trait TokenReceiver {
+        #[ink(message)]
+        fn on_token_received(&mut self, from: AccountId, value: Balance, data: Vec<u8>) -> [u8; 4];
+    }
+
+    /// Value returned to accept tokens, see `erc20::ON_TOKEN_RECEIVED`.
+    const ON_TOKEN_RECEIVED: [u8; 4] = ink::selector_bytes!("TokenReceiver::on_token_received");
+
+    #[ink(storage)]
+    pub struct TokenReceiverMock {
+        accept: bool,
+        last_received: Option<Received>,
+    }
+
+    impl TokenReceiverMock {
+        #[ink(constructor)]
+        pub fn new(accept: bool) -> Self {
+            Self {
+                accept,
+                last_received: None,
+            }
+        }
+
+        #[ink(message)]
+        pub fn last_received(&self) -> Option<Received> {
+            self.last_received.clone()
+        }
+    }
+
+    impl TokenReceiver for TokenReceiverMock {
+        #[ink(message)]
+        fn on_token_received(&mut self, from: AccountId, value: Balance, data: Vec<u8>) -> [u8; 4] {
+            self.last_received = Some((from, value, data));
+            if self.accept {
+                ON_TOKEN_RECEIVED
+            } else {
+                [0; 4]
+            }
+        }
+    }
+}